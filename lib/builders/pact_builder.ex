@@ -73,11 +73,13 @@ defmodule Pact.Builders.PactBuilder do
   def v4?(builder), do: Native.pact_builder_is_v4(builder)
 
   @doc """
-  Start a mock server running in a background thread. If the catalog entry is omitted, then a standard HTTP mock server will be started.
+  Start a mock server running in a background thread. If the `:transport` option is omitted and no interaction sets a transport, then a standard HTTP mock server will be started.
+
+  See `Pact.MockServer.start/2` for the supported options.
   """
-  @spec start_mock_server(builder :: Native.PactBuilder.t()) ::
+  @spec start_mock_server(builder :: Native.PactBuilder.t(), opts :: [MockServer.option()]) ::
           :ignore | {:error, any()} | {:ok, pid()}
-  def start_mock_server(builder), do: MockServer.start(builder)
+  def start_mock_server(builder, opts \\ []), do: MockServer.start(builder, opts)
 
   @doc """
   Returns an iterator over the asynchronous messages in the Pact
@@ -97,4 +99,29 @@ defmodule Pact.Builders.PactBuilder do
         ) :: Native.PactBuilder.t()
   def using_plugin(builder, name, version \\ nil),
     do: Native.pact_builder_using_plugin(builder, name, version)
+
+  @doc """
+  Sets the directory pact files are written to. Takes precedence over the
+  `PACT_OUTPUT_DIR` environment variable, so async test suites can write to
+  separate directories.
+  """
+  @spec output_dir(builder :: Native.PactBuilder.t(), dir :: String.t()) ::
+          Native.PactBuilder.t()
+  def output_dir(builder, dir), do: Native.pact_builder_output_dir(builder, dir)
+
+  @doc """
+  Sets whether an existing pact file is overwritten (`true`) or merged with
+  (`false`) when the pact is written. Takes precedence over the
+  `PACT_OVERWRITE` environment variable.
+  """
+  @spec overwrite(builder :: Native.PactBuilder.t(), value :: boolean()) ::
+          Native.PactBuilder.t()
+  def overwrite(builder, value), do: Native.pact_builder_overwrite(builder, value)
+
+  @doc """
+  Writes the pact file now, merging it with an existing file unless
+  overwriting is enabled. Returns the path of the written file.
+  """
+  @spec write_pact(builder :: Native.PactBuilder.t()) :: {:ok, String.t()} | {:error, String.t()}
+  def write_pact(builder), do: Native.pact_builder_write_pact(builder)
 end
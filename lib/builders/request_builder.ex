@@ -135,7 +135,7 @@ defmodule Pact.Builders.RequestBuilder do
   """
   @spec json_body(builder :: Native.RequestBuilder.t(), body :: Patterns.json_pattern()) ::
           Native.RequestBuilder.t()
-  def json_body(builder, body), do: Native.request_builder_json_body(builder, Jason.encode!(body))
+  def json_body(builder, body), do: Native.request_builder_json_body(builder, body)
 
   @doc """
   Specify a text body (text/plain) matching the given pattern.
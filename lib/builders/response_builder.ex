@@ -15,6 +15,29 @@ defmodule Pact.Builders.ResponseBuilder do
           Native.ResponseBuilder.t()
   def status(builder, status), do: Native.response_builder_status(builder, status)
 
+  @type status_class ::
+          :information
+          | :success
+          | :redirect
+          | :client_error
+          | :server_error
+          | :non_error
+          | :error
+
+  @doc """
+  Accept any status code of the given class, e.g. `:success` for any 2xx, or
+  any of the given status codes, of which there must be at least one. If the
+  current status code is not accepted, it is changed to one that is, e.g.
+  `200` for `:success`. Requires a V4 pact.
+  """
+  @spec status_matching(
+          buider :: Native.ResponseBuilder.t(),
+          status :: status_class() | [pos_integer()]
+        ) :: Native.ResponseBuilder.t()
+  def status_matching(builder, status)
+      when is_atom(status) or (is_list(status) and status != []),
+      do: Native.response_builder_status_matching(builder, status)
+
   @doc """
   Set the status code to `200 OK`. (This is default.)
   """
@@ -117,12 +140,11 @@ defmodule Pact.Builders.ResponseBuilder do
     do: Native.response_builder_body2(builder, body, content_type)
 
   @doc """
-  Specify a body literal. This does not allow using patterns.
+  Specify the body as `JsonPattern`, possibly including special matching rules.
   """
   @spec json_body(builder :: Native.ResponseBuilder.t(), body :: Patterns.json_pattern()) ::
           Native.ResponseBuilder.t()
-  def json_body(builder, body),
-    do: Native.response_builder_json_body(builder, Jason.encode!(body))
+  def json_body(builder, body), do: Native.response_builder_json_body(builder, body)
 
   @doc """
   Specify a text body (text/plain) matching the given pattern.
@@ -0,0 +1,162 @@
+defmodule Pact.Generators do
+  @moduledoc """
+  Generators replace the example of a pattern with a fresh value each time the
+  pattern is used, e.g. in the responses of a mock server or in the requests
+  sent during provider verification. Attach them to a pattern with
+  `Pact.Patterns.generate/2`.
+
+  Like a matcher, a generator with an invalid regular expression raises
+  `{:invalid_regex, path, message}` when the pattern is used.
+  """
+
+  @type uuid_format ::
+          :simple | :lower_case_hyphenated | :upper_case_hyphenated | :urn
+
+  @type t ::
+          {:random_int, %{min: integer(), max: integer()}}
+          | {:random_decimal, pos_integer()}
+          | {:random_hexadecimal, pos_integer()}
+          | {:random_string, pos_integer()}
+          | {:uuid, uuid_format() | nil}
+          | {:regex, String.t()}
+          | {:date, %{format: String.t() | nil, expression: String.t() | nil}}
+          | {:time, %{format: String.t() | nil, expression: String.t() | nil}}
+          | {:date_time, %{format: String.t() | nil, expression: String.t() | nil}}
+          | {:mock_server_url, %{example: String.t(), regex: String.t()}}
+
+  @doc """
+  Generates a random integer between `min` and `max`, inclusive. Both must
+  fit in a signed 32-bit integer.
+
+  ## Examples
+
+      iex> Pact.Generators.random_int(1, 100)
+      {:random_int, %{min: 1, max: 100}}
+  """
+  @spec random_int(integer(), integer()) :: t()
+  def random_int(min, max)
+      when min in -2_147_483_648..2_147_483_647 and max in -2_147_483_648..2_147_483_647 and
+             min <= max,
+      do: {:random_int, %{min: min, max: max}}
+
+  @doc """
+  Generates a random decimal number with the given number of digits.
+
+  ## Examples
+
+      iex> Pact.Generators.random_decimal(6)
+      {:random_decimal, 6}
+  """
+  @spec random_decimal(pos_integer()) :: t()
+  def random_decimal(digits) when is_integer(digits) and digits > 0,
+    do: {:random_decimal, digits}
+
+  @doc """
+  Generates a random string of the given number of hexadecimal digits.
+
+  ## Examples
+
+      iex> Pact.Generators.random_hexadecimal(8)
+      {:random_hexadecimal, 8}
+  """
+  @spec random_hexadecimal(pos_integer()) :: t()
+  def random_hexadecimal(digits) when is_integer(digits) and digits > 0,
+    do: {:random_hexadecimal, digits}
+
+  @doc """
+  Generates a random alphanumeric string of the given size.
+
+  ## Examples
+
+      iex> Pact.Generators.random_string(10)
+      {:random_string, 10}
+  """
+  @spec random_string(pos_integer()) :: t()
+  def random_string(size) when is_integer(size) and size > 0, do: {:random_string, size}
+
+  @doc """
+  Generates a random UUID, lower-case hyphenated unless another format is given.
+
+  ## Examples
+
+      iex> Pact.Generators.uuid()
+      {:uuid, nil}
+
+      iex> Pact.Generators.uuid(:urn)
+      {:uuid, :urn}
+  """
+  @spec uuid(uuid_format() | nil) :: t()
+  def uuid(format \\ nil)
+      when format in [nil, :simple, :lower_case_hyphenated, :upper_case_hyphenated, :urn],
+      do: {:uuid, format}
+
+  @doc """
+  Generates a random string matching the regular expression.
+
+  ## Examples
+
+      iex> Pact.Generators.regex("[a-z]{3}-\\\\d{2}")
+      {:regex, "[a-z]{3}-\\\\d{2}"}
+  """
+  @spec regex(String.t()) :: t()
+  def regex(regex) when is_binary(regex), do: {:regex, regex}
+
+  @doc """
+  Generates a date. Both options are optional.
+
+  ## Options
+    - `:format`: The format of the date, e.g. `"yyyy-MM-dd"` (default: ISO 8601)
+    - `:expression`: The date relative to today, e.g. `"today + 1 day"` or
+      `"next monday"` (default: today)
+
+  ## Examples
+
+      iex> Pact.Generators.date(format: "yyyy-MM-dd", expression: "today + 1 day")
+      {:date, %{format: "yyyy-MM-dd", expression: "today + 1 day"}}
+  """
+  @spec date(keyword()) :: t()
+  def date(opts \\ []), do: {:date, date_time_options(opts)}
+
+  @doc """
+  Generates a time. Takes the same options as `date/1`, with expressions such
+  as `"now + 2 hours"`.
+
+  ## Examples
+
+      iex> Pact.Generators.time(format: "HH:mm")
+      {:time, %{format: "HH:mm", expression: nil}}
+  """
+  @spec time(keyword()) :: t()
+  def time(opts \\ []), do: {:time, date_time_options(opts)}
+
+  @doc """
+  Generates a date and time. Takes the same options as `date/1`, with
+  expressions such as `"now + 1 day @ noon"`.
+
+  ## Examples
+
+      iex> Pact.Generators.datetime(expression: "now + 1 day")
+      {:date_time, %{format: nil, expression: "now + 1 day"}}
+  """
+  @spec datetime(keyword()) :: t()
+  def datetime(opts \\ []), do: {:date_time, date_time_options(opts)}
+
+  @doc """
+  Generates a URL pointing at the mock server. The part of `example` matched by
+  `regex` is replaced by the base URL of the mock server followed by the first
+  capture group.
+
+  ## Examples
+
+      iex> Pact.Generators.mock_server_url("http://localhost:1234/orders/1", ".*(/orders/\\\\d+)$")
+      {:mock_server_url, %{example: "http://localhost:1234/orders/1", regex: ".*(/orders/\\\\d+)$"}}
+  """
+  @spec mock_server_url(String.t(), String.t()) :: t()
+  def mock_server_url(example, regex) when is_binary(example) and is_binary(regex),
+    do: {:mock_server_url, %{example: example, regex: regex}}
+
+  defp date_time_options(opts) do
+    opts = Keyword.validate!(opts, [:format, :expression])
+    %{format: opts[:format], expression: opts[:expression]}
+  end
+end
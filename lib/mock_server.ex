@@ -4,18 +4,73 @@ defmodule Pact.MockServer do
   for use in tests, and validates that the requests made to that server are
   correct.
 
-  Because this is intended for use in tests, it will panic if something goes
-  wrong.
+  Failures are returned rather than raised: `start/2`, `verify/1` and `stop/1`
+  return `{:error, _}` when the mock server cannot be started, the requests
+  did not match, or the pact cannot be written.
   """
+  alias Pact.Models.MatchResult
+  alias Pact.Models.ReceivedRequest
   alias Pact.Native.PactConsumer, as: Native
   use GenServer
 
   defstruct mock_server: nil
 
   # client
-  @spec start(pact_builder :: Native.PactBuilder.t()) :: :ignore | {:error, any()} | {:ok, pid()}
-  def start(pact_builder) do
-    GenServer.start_link(__MODULE__, pact_builder)
+
+  @type option ::
+          {:port, non_neg_integer()}
+          | {:host, String.t()}
+          | {:transport, String.t()}
+          | {:cors_preflight, boolean()}
+          | {:tls, boolean()}
+          | {:owner, pid() | nil}
+
+  @doc """
+  Starts the mock server for the given pact. Returns `{:error, reason}` if the
+  mock server could not be started, e.g. because the port is already in use or
+  a required plugin is not installed.
+
+  ## Options
+    - `:port` - the port to bind to. Defaults to a random free port.
+    - `:host` - the address to bind to, e.g. `"0.0.0.0"`. Defaults to `"127.0.0.1"`.
+    - `:transport` - the catalog entry of the transport to use, e.g. `"grpc"`.
+      Mock servers for transports other than HTTP are hosted by the plugin
+      providing the transport, which must have been loaded with
+      `Pact.Builders.PactBuilder.using_plugin/3`. Defaults to the first non-HTTP
+      transport set on an interaction with `Pact.Builders.InteractionBuilder.transport/2`,
+      or HTTP if there is none.
+    - `:cors_preflight` - whether CORS pre-flight requests should be answered. Defaults to `false`.
+    - `:tls` - serve HTTPS using a generated self-signed certificate, see `certificate/1`. Defaults to `false`.
+    - `:owner` - a process to notify of every request the mock server handles.
+      It receives `{:pact_request, method, path, matched?}` messages, where
+      `matched?` is `false` for requests that did not match an interaction.
+      Each of those is followed by `{:pact_mismatch, mismatch}`, `mismatch`
+      being a `Pact.Models.MatchResult.RequestMismatch` or
+      `Pact.Models.MatchResult.RequestNotFound`. Only HTTP mock servers send
+      notifications. Defaults to `nil`.
+
+  ## Examples
+
+      {:ok, pid} = Pact.MockServer.start(pact_builder, owner: self())
+      HTTPoison.get!(Pact.MockServer.path(pid, "/mallory"))
+      assert_receive {:pact_request, "GET", "/mallory", true}
+  """
+  @spec start(pact_builder :: Native.PactBuilder.t(), opts :: [option()]) ::
+          :ignore | {:error, any()} | {:ok, pid()}
+  def start(pact_builder, opts \\ []) do
+    opts =
+      Keyword.validate!(opts,
+        port: nil,
+        host: nil,
+        transport: nil,
+        cors_preflight: false,
+        tls: false,
+        owner: nil
+      )
+
+    with {:ok, mock_server} <- Native.mock_server_start(pact_builder, Map.new(opts)) do
+      GenServer.start_link(__MODULE__, mock_server)
+    end
   end
 
   @doc """
@@ -27,6 +82,15 @@ defmodule Pact.MockServer do
     GenServer.call(pid, :url)
   end
 
+  @doc """
+  The port the mock server is listening on.
+  """
+  @spec port(pid :: pid()) :: non_neg_integer()
+  def port(pid) do
+    %URI{port: port} = pid |> url() |> URI.parse()
+    port
+  end
+
   @doc """
   Given a path string, return a URL pointing to that path on the mock
   server. If the path cannot be parsed as URL, **this function will
@@ -38,11 +102,78 @@ defmodule Pact.MockServer do
     GenServer.call(pid, {:path, path})
   end
 
+  @doc """
+  The PEM encoded self-signed certificate presented by a mock server started
+  with `tls: true`, or `nil` for plain HTTP mock servers. Configure the HTTP
+  client under test to trust it.
+  """
+  @spec certificate(pid :: pid()) :: String.t() | nil
+  def certificate(pid) do
+    GenServer.call(pid, :certificate)
+  end
+
+  @doc """
+  The metrics collected by the mock server: the total number of requests
+  received and the number of requests received per path.
+
+  ## Examples
+
+      %{requests: 3, requests_by_path: %{"/mallory" => 3}} = Pact.MockServer.metrics(pid)
+  """
+  @spec metrics(pid :: pid()) :: %{
+          requests: non_neg_integer(),
+          requests_by_path: %{optional(String.t()) => non_neg_integer()}
+        }
+  def metrics(pid) do
+    GenServer.call(pid, :metrics)
+  end
+
+  @doc """
+  The requests received so far, in the order they arrived, along with the
+  interaction each one was matched against. Only HTTP mock servers record
+  received requests; for plugin transports the list is always empty.
+  """
+  @spec received_requests(pid :: pid()) :: [ReceivedRequest.t()]
+  def received_requests(pid) do
+    GenServer.call(pid, :received_requests)
+  end
+
+  @doc """
+  Checks the requests received so far against the interactions in the pact.
+  Returns `:ok` if every expected request was received and matched, otherwise
+  `{:error, mismatches}` where each mismatch is one of the
+  `Pact.Models.MatchResult` structs.
+
+  Mock servers for plugin transports such as gRPC ask the plugin for its
+  results instead: each failing path is returned as a
+  `Pact.Models.MatchResult.PluginMismatch`. If the plugin cannot be asked,
+  `{:error, reason}` is returned.
+  """
+  @spec verify(pid :: pid()) :: :ok | {:error, [MatchResult.t()] | String.t()}
+  def verify(pid) do
+    GenServer.call(pid, :verify)
+  end
+
+  @doc """
+  Stops the mock server. If every expected request was received and matched,
+  the pact file is written and `{:ok, pact_path}` is returned. Otherwise
+  returns `{:error, mismatches}` as `verify/1` does, or `{:error, reason}` if
+  the pact could not be written or the plugin hosting the mock server could
+  not be reached.
+
+  A mock server that is not stopped explicitly is stopped when it terminates,
+  e.g. when the test process that started it exits.
+  """
+  @spec stop(pid :: pid()) :: {:ok, String.t()} | {:error, [MatchResult.t()] | String.t()}
+  def stop(pid) do
+    GenServer.call(pid, :stop)
+  end
+
   # Server (callbacks)
 
   @impl true
-  def init(pact_builder) do
-    mock_server = Native.mock_server_start(pact_builder)
+  def init(mock_server) do
+    Process.flag(:trap_exit, true)
     {:ok, %__MODULE__{mock_server: mock_server}}
   end
 
@@ -53,4 +184,32 @@ defmodule Pact.MockServer do
   @impl true
   def handle_call({:path, path}, _from, state = %__MODULE__{mock_server: mock_server}),
     do: {:reply, Native.mock_server_path(mock_server, path), state}
+
+  @impl true
+  def handle_call(:certificate, _from, state = %__MODULE__{mock_server: mock_server}),
+    do: {:reply, Native.mock_server_certificate(mock_server), state}
+
+  @impl true
+  def handle_call(:metrics, _from, state = %__MODULE__{mock_server: mock_server}),
+    do: {:reply, Native.mock_server_metrics(mock_server), state}
+
+  @impl true
+  def handle_call(:received_requests, _from, state = %__MODULE__{mock_server: mock_server}),
+    do: {:reply, Native.mock_server_received_requests(mock_server), state}
+
+  @impl true
+  def handle_call(:verify, _from, state = %__MODULE__{mock_server: mock_server}),
+    do: {:reply, Native.mock_server_verify(mock_server), state}
+
+  @impl true
+  def handle_call(:stop, _from, state = %__MODULE__{mock_server: mock_server}),
+    do: {:stop, :normal, Native.mock_server_stop(mock_server), %{state | mock_server: nil}}
+
+  @impl true
+  def terminate(_reason, %__MODULE__{mock_server: nil}), do: :ok
+
+  def terminate(_reason, %__MODULE__{mock_server: mock_server}) do
+    Native.mock_server_stop(mock_server)
+    :ok
+  end
 end
@@ -0,0 +1,86 @@
+defmodule Pact.Models.MatchResult do
+  @moduledoc """
+  The outcome of matching a request received by the mock server against the
+  interactions of the pact. `interaction` holds the description of the
+  interaction the expected request belongs to, when it can be resolved.
+  """
+  alias Pact.Models.Mismatch
+  alias Pact.Models.RequestDetails
+
+  defmodule RequestMatch do
+    @moduledoc """
+    The request matched its interaction.
+    """
+    @enforce_keys [:expected, :actual]
+    defstruct [:interaction, :expected, :actual]
+
+    @type t :: %__MODULE__{
+            interaction: String.t() | nil,
+            expected: RequestDetails.t(),
+            actual: RequestDetails.t()
+          }
+  end
+
+  defmodule RequestMismatch do
+    @moduledoc """
+    The request was routed to an interaction but did not match it.
+    """
+    @enforce_keys [:expected, :actual, :mismatches]
+    defstruct [:interaction, :expected, :actual, :mismatches]
+
+    @type t :: %__MODULE__{
+            interaction: String.t() | nil,
+            expected: RequestDetails.t(),
+            actual: RequestDetails.t(),
+            mismatches: [Mismatch.t()]
+          }
+  end
+
+  defmodule RequestNotFound do
+    @moduledoc """
+    The request was not expected by any interaction.
+    """
+    @enforce_keys [:request]
+    defstruct [:request]
+
+    @type t :: %__MODULE__{
+            request: RequestDetails.t()
+          }
+  end
+
+  defmodule MissingRequest do
+    @moduledoc """
+    An expected request was never received.
+    """
+    @enforce_keys [:request]
+    defstruct [:interaction, :request]
+
+    @type t :: %__MODULE__{
+            interaction: String.t() | nil,
+            request: RequestDetails.t()
+          }
+  end
+
+  defmodule PluginMismatch do
+    @moduledoc """
+    A failure reported by the plugin behind a mock server for a transport such
+    as gRPC. Plugins only report the path, e.g. the gRPC method, with either an
+    error or the mismatches they found, so no request details are available.
+    """
+    @enforce_keys [:path, :mismatches]
+    defstruct [:path, :error, :mismatches]
+
+    @type t :: %__MODULE__{
+            path: String.t(),
+            error: String.t() | nil,
+            mismatches: [Mismatch.t()]
+          }
+  end
+
+  @type t ::
+          RequestMatch.t()
+          | RequestMismatch.t()
+          | RequestNotFound.t()
+          | MissingRequest.t()
+          | PluginMismatch.t()
+end
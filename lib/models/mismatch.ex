@@ -0,0 +1,134 @@
+defmodule Pact.Models.Mismatch do
+  @moduledoc """
+  A single difference between an expected and an actual request, as reported by
+  the mock server when a request does not match its interaction.
+  """
+
+  defmodule MethodMismatch do
+    @moduledoc """
+    The request method did not match.
+    """
+    @enforce_keys [:expected, :actual, :mismatch]
+    defstruct [:expected, :actual, :mismatch]
+
+    @type t :: %__MODULE__{
+            expected: String.t(),
+            actual: String.t(),
+            mismatch: String.t()
+          }
+  end
+
+  defmodule PathMismatch do
+    @moduledoc """
+    The request path did not match.
+    """
+    @enforce_keys [:expected, :actual, :mismatch]
+    defstruct [:expected, :actual, :mismatch]
+
+    @type t :: %__MODULE__{
+            expected: String.t(),
+            actual: String.t(),
+            mismatch: String.t()
+          }
+  end
+
+  defmodule StatusMismatch do
+    @moduledoc """
+    The response status did not match.
+    """
+    @enforce_keys [:expected, :actual, :mismatch]
+    defstruct [:expected, :actual, :mismatch]
+
+    @type t :: %__MODULE__{
+            expected: non_neg_integer(),
+            actual: non_neg_integer(),
+            mismatch: String.t()
+          }
+  end
+
+  defmodule QueryMismatch do
+    @moduledoc """
+    A query parameter did not match.
+    """
+    @enforce_keys [:parameter, :expected, :actual, :mismatch]
+    defstruct [:parameter, :expected, :actual, :mismatch]
+
+    @type t :: %__MODULE__{
+            parameter: String.t(),
+            expected: String.t(),
+            actual: String.t(),
+            mismatch: String.t()
+          }
+  end
+
+  defmodule HeaderMismatch do
+    @moduledoc """
+    A header did not match.
+    """
+    @enforce_keys [:key, :expected, :actual, :mismatch]
+    defstruct [:key, :expected, :actual, :mismatch]
+
+    @type t :: %__MODULE__{
+            key: String.t(),
+            expected: String.t(),
+            actual: String.t(),
+            mismatch: String.t()
+          }
+  end
+
+  defmodule BodyTypeMismatch do
+    @moduledoc """
+    The content type of the body did not match.
+    """
+    @enforce_keys [:expected, :actual, :mismatch]
+    defstruct [:expected, :actual, :mismatch, :expected_body, :actual_body]
+
+    @type t :: %__MODULE__{
+            expected: String.t(),
+            actual: String.t(),
+            mismatch: String.t(),
+            expected_body: binary() | nil,
+            actual_body: binary() | nil
+          }
+  end
+
+  defmodule BodyMismatch do
+    @moduledoc """
+    An element of the body did not match. `path` points at the offending element.
+    """
+    @enforce_keys [:path, :mismatch]
+    defstruct [:path, :expected, :actual, :mismatch]
+
+    @type t :: %__MODULE__{
+            path: String.t(),
+            expected: binary() | nil,
+            actual: binary() | nil,
+            mismatch: String.t()
+          }
+  end
+
+  defmodule MetadataMismatch do
+    @moduledoc """
+    A message metadata entry did not match.
+    """
+    @enforce_keys [:key, :expected, :actual, :mismatch]
+    defstruct [:key, :expected, :actual, :mismatch]
+
+    @type t :: %__MODULE__{
+            key: String.t(),
+            expected: String.t(),
+            actual: String.t(),
+            mismatch: String.t()
+          }
+  end
+
+  @type t ::
+          MethodMismatch.t()
+          | PathMismatch.t()
+          | StatusMismatch.t()
+          | QueryMismatch.t()
+          | HeaderMismatch.t()
+          | BodyTypeMismatch.t()
+          | BodyMismatch.t()
+          | MetadataMismatch.t()
+end
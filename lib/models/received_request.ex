@@ -0,0 +1,17 @@
+defmodule Pact.Models.ReceivedRequest do
+  @moduledoc """
+  A request received by the mock server. `interaction` is the description of
+  the interaction the request was matched against, if any, and `matched`
+  tells whether it satisfied that interaction.
+  """
+  alias Pact.Models.RequestDetails
+
+  @enforce_keys [:request, :matched]
+  defstruct [:request, :interaction, :matched]
+
+  @type t :: %__MODULE__{
+          request: RequestDetails.t(),
+          interaction: String.t() | nil,
+          matched: boolean()
+        }
+end
@@ -0,0 +1,16 @@
+defmodule Pact.Models.RequestDetails do
+  @moduledoc """
+  An HTTP request as seen by the mock server, either the one expected by an
+  interaction or the one actually received.
+  """
+  @enforce_keys [:method, :path]
+  defstruct [:method, :path, :query, :headers, :body]
+
+  @type t :: %__MODULE__{
+          method: String.t(),
+          path: String.t(),
+          query: %{optional(String.t()) => [String.t() | nil]} | nil,
+          headers: %{optional(String.t()) => [String.t()]} | nil,
+          body: binary() | nil
+        }
+end
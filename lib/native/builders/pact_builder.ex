@@ -45,6 +45,18 @@ defmodule Pact.Native.Builders.PactBuilder do
             ) :: PactBuilder.t()
       def pact_builder_using_plugin(builder, name, version),
         do: :erlang.nif_error(:nif_not_loaded)
+
+      @spec pact_builder_output_dir(builder :: PactBuilder.t(), dir :: String.t()) ::
+              PactBuilder.t()
+      def pact_builder_output_dir(_builder, _dir), do: :erlang.nif_error(:nif_not_loaded)
+
+      @spec pact_builder_overwrite(builder :: PactBuilder.t(), value :: boolean()) ::
+              PactBuilder.t()
+      def pact_builder_overwrite(_builder, _value), do: :erlang.nif_error(:nif_not_loaded)
+
+      @spec pact_builder_write_pact(builder :: PactBuilder.t()) ::
+              {:ok, String.t()} | {:error, String.t()}
+      def pact_builder_write_pact(_builder), do: :erlang.nif_error(:nif_not_loaded)
     end
   end
 end
@@ -26,6 +26,13 @@ defmodule Pact.Native.Builders.ResponseBuilder do
               ResponseBuilder.t()
       def response_builder_status(_builder, _status), do: :erlang.nif_error(:nif_not_loaded)
 
+      @spec response_builder_status_matching(
+              buider :: ResponseBuilder.t(),
+              status :: Pact.Builders.ResponseBuilder.status_class() | [pos_integer()]
+            ) :: ResponseBuilder.t()
+      def response_builder_status_matching(_builder, _status),
+        do: :erlang.nif_error(:nif_not_loaded)
+
       @spec response_builder_ok(buider :: ResponseBuilder.t()) :: ResponseBuilder.t()
       def response_builder_ok(_builder), do: :erlang.nif_error(:nif_not_loaded)
 
@@ -1,6 +1,8 @@
 defmodule Pact.Native.MockServer do
   @moduledoc false
 
+  alias Pact.Models.MatchResult
+  alias Pact.Models.ReceivedRequest
   alias Pact.Native.PactConsumer.PactBuilder
 
   defmacro __using__(_opts) do
@@ -26,9 +28,45 @@ defmodule Pact.Native.MockServer do
       def mock_server_path(_mock_server, _path),
         do: :erlang.nif_error(:nif_not_loaded)
 
-      @spec mock_server_start(pact_builder :: PactBuilder.t()) ::
-              String.t()
-      def mock_server_start(_pact_builder),
+      @spec mock_server_certificate(mock_server :: ValidatingMockServer.t()) :: String.t() | nil
+      def mock_server_certificate(_mock_server),
+        do: :erlang.nif_error(:nif_not_loaded)
+
+      @spec mock_server_metrics(mock_server :: ValidatingMockServer.t()) :: %{
+              requests: non_neg_integer(),
+              requests_by_path: %{optional(String.t()) => non_neg_integer()}
+            }
+      def mock_server_metrics(_mock_server),
+        do: :erlang.nif_error(:nif_not_loaded)
+
+      @spec mock_server_received_requests(mock_server :: ValidatingMockServer.t()) ::
+              [ReceivedRequest.t()]
+      def mock_server_received_requests(_mock_server),
+        do: :erlang.nif_error(:nif_not_loaded)
+
+      @spec mock_server_verify(mock_server :: ValidatingMockServer.t()) ::
+              :ok | {:error, [MatchResult.t()] | String.t()}
+      def mock_server_verify(_mock_server),
+        do: :erlang.nif_error(:nif_not_loaded)
+
+      @spec mock_server_stop(mock_server :: ValidatingMockServer.t()) ::
+              {:ok, String.t()} | {:error, [MatchResult.t()] | String.t()}
+      def mock_server_stop(_mock_server),
+        do: :erlang.nif_error(:nif_not_loaded)
+
+      @spec mock_server_start(
+              pact_builder :: PactBuilder.t(),
+              options :: %{
+                port: non_neg_integer() | nil,
+                host: String.t() | nil,
+                transport: String.t() | nil,
+                cors_preflight: boolean(),
+                tls: boolean(),
+                owner: pid() | nil
+              }
+            ) ::
+              {:ok, ValidatingMockServer.t()} | {:error, String.t()}
+      def mock_server_start(_pact_builder, _options),
         do: :erlang.nif_error(:nif_not_loaded)
     end
   end
@@ -27,6 +27,7 @@ defmodule Pact.Native.PactConsumer do
   use Pact.Native.Builders.RequestBuilder
   use Pact.Native.Builders.ResponseBuilder
   use Pact.Native.MockServer
+  use Pact.Native.Patterns
   use Pact.Native.Models.V4.AsyncMessage
   use Pact.Native.Models.V4.HttpParts
   use Pact.Native.Models.Interaction
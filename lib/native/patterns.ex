@@ -0,0 +1,13 @@
+defmodule Pact.Native.Patterns do
+  @moduledoc false
+
+  alias Pact.Patterns
+
+  defmacro __using__(_opts) do
+    quote do
+      @spec patterns_render(pattern :: Patterns.json_pattern()) ::
+              {String.t(), String.t(), String.t()}
+      def patterns_render(_pattern), do: :erlang.nif_error(:nif_not_loaded)
+    end
+  end
+end
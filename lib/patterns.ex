@@ -4,8 +4,18 @@ defmodule Pact.Patterns do
 
   JSON patterns can include various data types and matchers, while string patterns
   are limited to strings and specific matchers.
+
+  A matcher with an invalid regular expression raises
+  `{:invalid_regex, path, message}` when the pattern is used, `path` being the
+  JSON path of the matcher, e.g. `"$.users[0].id"`.
+
+  Each example must also satisfy its own matchers, so that the contract can be
+  honoured by a provider. Otherwise `{:invalid_examples, [{path, message}]}` is
+  raised, listing every violation.
   """
 
+  alias Pact.Native.PactConsumer, as: Native
+
   @type json_pattern ::
           integer()
           | float()
@@ -20,8 +30,30 @@ defmodule Pact.Patterns do
   @type json_matcher ::
           {:matching_regex, %{regex: String.t(), example: String.t()}}
           | {:like, json_pattern()}
-          | {:each_like, %{json_pattern: json_pattern(), min_len: non_neg_integer()}}
+          | {:each_like,
+             %{
+               json_pattern: json_pattern(),
+               min_len: non_neg_integer(),
+               max_len: pos_integer() | nil
+             }}
           | {:date_time, %{format: String.t(), example: String.t()}}
+          | {:integer, integer() | nil}
+          | {:decimal, float() | nil}
+          | {:number, number() | nil}
+          | {:boolean, boolean() | nil}
+          | {:null, nil}
+          | {:include, String.t()}
+          | {:not_empty, json_pattern()}
+          | {:min_type, %{json_pattern: json_pattern(), min: non_neg_integer()}}
+          | {:max_type, %{json_pattern: json_pattern(), max: pos_integer()}}
+          | {:each_key, %{json_pattern: json_pattern(), key_pattern: string_pattern()}}
+          | {:each_value, %{json_pattern: json_pattern(), value_pattern: json_pattern()}}
+          | {:array_contains, [json_pattern()]}
+          | {:semver, String.t()}
+          | {:content_type, %{content_type: String.t(), example: String.t()}}
+          | {:generated, %{pattern: json_pattern(), generator: Pact.Generators.t()}}
+          | {:all_of, [json_pattern()]}
+          | {:any_of, [json_pattern()]}
 
   @type string_pattern ::
           binary()
@@ -31,6 +63,15 @@ defmodule Pact.Patterns do
           {:matching_regex, %{regex: String.t(), example: String.t()}}
           | {:like, string_pattern()}
           | {:date_time, %{format: String.t(), example: String.t()}}
+          | {:boolean, boolean() | nil}
+          | {:null, nil}
+          | {:include, String.t()}
+          | {:not_empty, string_pattern()}
+          | {:semver, String.t()}
+          | {:content_type, %{content_type: String.t(), example: String.t()}}
+          | {:generated, %{pattern: string_pattern(), generator: Pact.Generators.t()}}
+          | {:all_of, [string_pattern()]}
+          | {:any_of, [string_pattern()]}
 
   @doc """
   Creates a datetime matcher for both JSON and string patterns.
@@ -56,27 +97,293 @@ defmodule Pact.Patterns do
   def like(pattern), do: {:like, pattern}
 
   @doc """
-  Creates an each_like matcher for JSON arrays with optional minimum length.
+  Creates an each_like matcher for JSON arrays with optional minimum and maximum
+  length. The generated example repeats the pattern `min_len` times.
 
   ## Parameters
     - pattern: The example pattern for array elements
     - min_len: Minimum required array length (default: 1, must be ≥ 0)
+    - max_len: Maximum allowed array length (default: unbounded, must be ≥ min_len)
 
   ## Examples
       # With default minimum length
       iex> Pact.Patterns.each_like("item")
-      {:each_like, %{json_pattern: "item", min_len: 1}}
+      {:each_like, %{json_pattern: "item", min_len: 1, max_len: nil}}
 
       # With explicit minimum length
       iex> Pact.Patterns.each_like("item", 3)
-      {:each_like, %{json_pattern: "item", min_len: 3}}
+      {:each_like, %{json_pattern: "item", min_len: 3, max_len: nil}}
+
+      # With explicit minimum and maximum length
+      iex> Pact.Patterns.each_like("item", 1, 10)
+      {:each_like, %{json_pattern: "item", min_len: 1, max_len: 10}}
   """
   @spec each_like(json_pattern()) :: json_matcher()
   @spec each_like(json_pattern(), non_neg_integer()) :: json_matcher()
-  def each_like(pattern, min_len \\ 1)
+  @spec each_like(json_pattern(), non_neg_integer(), pos_integer() | nil) :: json_matcher()
+  def each_like(pattern, min_len \\ 1, max_len \\ nil)
+
+  def each_like(pattern, min_len, nil) when is_integer(min_len) and min_len >= 0,
+    do: {:each_like, %{json_pattern: pattern, min_len: min_len, max_len: nil}}
+
+  def each_like(pattern, min_len, max_len)
+      when is_integer(min_len) and min_len >= 0 and is_integer(max_len) and max_len >= 1 and
+             max_len >= min_len,
+      do: {:each_like, %{json_pattern: pattern, min_len: min_len, max_len: max_len}}
+
+  @doc """
+  Creates an each_like matcher for JSON arrays with between `min_len` and
+  `max_len` elements (alias for each_like/3).
+
+  ## Examples
+
+      iex> Pact.Patterns.min_max_like("item", 1, 10)
+      {:each_like, %{json_pattern: "item", min_len: 1, max_len: 10}}
+  """
+  @spec min_max_like(json_pattern(), non_neg_integer(), pos_integer()) :: json_matcher()
+  def min_max_like(pattern, min_len, max_len), do: each_like(pattern, min_len, max_len)
+
+  @doc """
+  Creates a matcher for JSON arrays with at least `min` elements, each matching
+  the elements of the example array by type.
+
+  ## Examples
+
+      iex> Pact.Patterns.min_type(["a", "b"], 1)
+      {:min_type, %{json_pattern: ["a", "b"], min: 1}}
+  """
+  @spec min_type(json_pattern(), non_neg_integer()) :: json_matcher()
+  def min_type(pattern, min) when is_integer(min) and min >= 0,
+    do: {:min_type, %{json_pattern: pattern, min: min}}
+
+  @doc """
+  Creates a matcher for JSON arrays with at most `max` elements, each matching
+  the elements of the example array by type.
+
+  ## Examples
+
+      iex> Pact.Patterns.max_type(["a", "b"], 10)
+      {:max_type, %{json_pattern: ["a", "b"], max: 10}}
+  """
+  @spec max_type(json_pattern(), pos_integer()) :: json_matcher()
+  def max_type(pattern, max) when is_integer(max) and max >= 1,
+    do: {:max_type, %{json_pattern: pattern, max: max}}
+
+  @doc """
+  Creates a matcher for JSON objects whose keys all match `key_pattern`, e.g.
+  maps keyed by IDs. The given object is used as the example, and the rules of
+  its values apply to the values under every key. Requires a V4 pact.
+
+  Combine with `each_value/2` to match both the keys and the values.
+
+  ## Examples
+
+      iex> Pact.Patterns.each_key(%{"42" => "Mallory"}, Pact.Patterns.matching_regex("^\\d+$", "42"))
+      {:each_key,
+       %{
+         json_pattern: %{"42" => "Mallory"},
+         key_pattern: {:matching_regex, %{regex: "^\\d+$", example: "42"}}
+       }}
+  """
+  @spec each_key(json_pattern(), string_pattern()) :: json_matcher()
+  def each_key(pattern, key_pattern),
+    do: {:each_key, %{json_pattern: pattern, key_pattern: key_pattern}}
 
-  def each_like(pattern, min_len) when is_integer(min_len) and min_len >= 0,
-    do: {:each_like, %{json_pattern: pattern, min_len: min_len}}
+  @doc """
+  Creates a matcher for JSON objects whose values all match `value_pattern`,
+  whatever their keys. The given object is used as the example. Requires a V4
+  pact.
+
+  ## Examples
+
+      iex> %{"42" => "Mallory"}
+      ...> |> Pact.Patterns.each_key(Pact.Patterns.matching_regex("^\\d+$", "42"))
+      ...> |> Pact.Patterns.each_value(Pact.Patterns.like("Mallory"))
+      {:each_value,
+       %{
+         json_pattern:
+           {:each_key,
+            %{
+              json_pattern: %{"42" => "Mallory"},
+              key_pattern: {:matching_regex, %{regex: "^\\d+$", example: "42"}}
+            }},
+         value_pattern: {:like, "Mallory"}
+       }}
+  """
+  @spec each_value(json_pattern(), json_pattern()) :: json_matcher()
+  def each_value(pattern, value_pattern),
+    do: {:each_value, %{json_pattern: pattern, value_pattern: value_pattern}}
+
+  @doc """
+  Creates a matcher for values matching all of the given matchers, e.g. a string
+  that has the type of the example and includes "EUR". The example comes from
+  the first matcher, and must satisfy the others.
+
+  ## Examples
+
+      iex> Pact.Patterns.all_of([Pact.Patterns.like("100 EUR"), Pact.Patterns.include("EUR")])
+      {:all_of, [{:like, "100 EUR"}, {:include, "EUR"}]}
+  """
+  @spec all_of([json_pattern()]) :: json_matcher()
+  @spec all_of([string_pattern()]) :: string_matcher()
+  def all_of(matchers) when is_list(matchers) and matchers != [], do: {:all_of, matchers}
+
+  @doc """
+  Creates a matcher for values matching any of the given matchers. The example
+  comes from the first matcher.
+
+  ## Examples
+
+      iex> Pact.Patterns.any_of([Pact.Patterns.include("EUR"), Pact.Patterns.include("USD")])
+      {:any_of, [{:include, "EUR"}, {:include, "USD"}]}
+  """
+  @spec any_of([json_pattern()]) :: json_matcher()
+  @spec any_of([string_pattern()]) :: string_matcher()
+  def any_of(matchers) when is_list(matchers) and matchers != [], do: {:any_of, matchers}
+
+  @doc """
+  Creates a matcher for JSON arrays containing at least one element matching
+  each of the variants, in any order and alongside any other elements. The
+  example has one element per variant. Requires a V4 pact.
+
+  ## Examples
+
+      iex> Pact.Patterns.array_contains([%{"type" => "login"}, %{"type" => "logout"}])
+      {:array_contains, [%{"type" => "login"}, %{"type" => "logout"}]}
+  """
+  @spec array_contains([json_pattern()]) :: json_matcher()
+  def array_contains(variants) when is_list(variants) and variants != [],
+    do: {:array_contains, variants}
+
+  @doc """
+  Creates a matcher for JSON integers. The example defaults to `13`. Integers
+  beyond 64 bits are written as strings in JSON, so they cannot be examples.
+
+  ## Examples
+
+      iex> Pact.Patterns.integer(42)
+      {:integer, 42}
+
+      iex> Pact.Patterns.integer()
+      {:integer, nil}
+  """
+  @spec integer(integer() | nil) :: json_matcher()
+  def integer(example \\ nil) when is_integer(example) or is_nil(example),
+    do: {:integer, example}
+
+  @doc """
+  Creates a matcher for JSON numbers with a fractional part. The example
+  defaults to `13.01`.
+
+  ## Examples
+
+      iex> Pact.Patterns.decimal(9.99)
+      {:decimal, 9.99}
+  """
+  @spec decimal(float() | nil) :: json_matcher()
+  def decimal(example \\ nil) when is_float(example) or is_nil(example),
+    do: {:decimal, example}
+
+  @doc """
+  Creates a matcher for any JSON number. The example defaults to `13`.
+
+  ## Examples
+
+      iex> Pact.Patterns.number(1.5)
+      {:number, 1.5}
+  """
+  @spec number(number() | nil) :: json_matcher()
+  def number(example \\ nil) when is_number(example) or is_nil(example),
+    do: {:number, example}
+
+  @doc """
+  Creates a boolean matcher for both JSON and string patterns. The example
+  defaults to `true`.
+
+  ## Examples
+
+      iex> Pact.Patterns.boolean(false)
+      {:boolean, false}
+  """
+  @spec boolean(boolean() | nil) :: json_matcher() | string_matcher()
+  def boolean(example \\ nil) when is_boolean(example) or is_nil(example),
+    do: {:boolean, example}
+
+  @doc """
+  Creates a matcher for null values for both JSON and string patterns.
+
+  ## Examples
+
+      iex> Pact.Patterns.null()
+      {:null, nil}
+  """
+  @spec null() :: json_matcher() | string_matcher()
+  def null, do: {:null, nil}
+
+  @doc """
+  Creates a matcher for strings containing the given substring, for both JSON
+  and string patterns. The substring is also used as the example.
+
+  ## Examples
+
+      iex> Pact.Patterns.include("Mallory")
+      {:include, "Mallory"}
+  """
+  @spec include(String.t()) :: json_matcher() | string_matcher()
+  def include(substring) when is_binary(substring), do: {:include, substring}
+
+  @doc """
+  Creates a matcher for values that are not empty, e.g. non-empty strings or
+  arrays, for both JSON and string patterns. When used in string patterns, the
+  inner pattern must be a valid string pattern. Requires a V4 pact.
+
+  ## Examples
+
+      iex> Pact.Patterns.not_empty(["item"])
+      {:not_empty, ["item"]}
+  """
+  @spec not_empty(json_pattern()) :: json_matcher()
+  def not_empty(pattern), do: {:not_empty, pattern}
+
+  @doc """
+  Creates a matcher for semantic version strings, for both JSON and string
+  patterns.
+
+  ## Examples
+
+      iex> Pact.Patterns.semver("1.2.3")
+      {:semver, "1.2.3"}
+  """
+  @spec semver(String.t()) :: json_matcher() | string_matcher()
+  def semver(example) when is_binary(example), do: {:semver, example}
+
+  @doc """
+  Creates a matcher for values whose content is of the given MIME type, for
+  both JSON and string patterns.
+
+  ## Examples
+
+      iex> Pact.Patterns.content_type("text/xml", "<?xml version=\"1.0\"?><a/>")
+      {:content_type, %{content_type: "text/xml", example: "<?xml version=\"1.0\"?><a/>"}}
+  """
+  @spec content_type(String.t(), String.t()) :: json_matcher() | string_matcher()
+  def content_type(content_type, example) when is_binary(content_type) and is_binary(example),
+    do: {:content_type, %{content_type: content_type, example: example}}
+
+  @doc """
+  Attaches a generator to a pattern, so that a fresh value replaces its example
+  wherever one is generated, e.g. in mock server responses. The pattern still
+  provides the example and the matching rules. Generators apply to the JSON
+  bodies of requests, responses and messages, and to headers.
+
+  ## Examples
+
+      iex> Pact.Patterns.integer(42) |> Pact.Patterns.generate(Pact.Generators.random_int(1, 100))
+      {:generated, %{pattern: {:integer, 42}, generator: {:random_int, %{min: 1, max: 100}}}}
+  """
+  @spec generate(json_pattern(), Pact.Generators.t()) :: json_matcher()
+  @spec generate(string_pattern(), Pact.Generators.t()) :: string_matcher()
+  def generate(pattern, generator), do: {:generated, %{pattern: pattern, generator: generator}}
 
   @doc """
   Creates a regex matcher (alias for matching_regex/2).
@@ -101,7 +408,26 @@ defmodule Pact.Patterns do
   def matching_regex(regex, example), do: {:matching_regex, %{regex: regex, example: example}}
 
   @doc """
-  Builds a JSON pattern structure.
+  Renders a JSON pattern without building a pact, e.g. for debugging or snapshot
+  tests. Returns the example, the matching rules and the generators, the last
+  two keyed by JSON path as in a V4 pact file.
+
+  ## Examples
+
+      iex> Pact.Patterns.render(%{"id" => Pact.Patterns.integer(42)})
+      {%{"id" => 42}, %{"$.id" => %{"combine" => "AND", "matchers" => [%{"match" => "integer"}]}},
+       %{}}
+  """
+  @spec render(json_pattern()) :: {term(), map(), map()}
+  def render(pattern) do
+    {example, matching_rules, generators} = Native.patterns_render(pattern)
+    {Jason.decode!(example), Jason.decode!(matching_rules), Jason.decode!(generators)}
+  end
+
+  @doc """
+  Builds a JSON pattern structure. Integers and floats are written as given,
+  except integers beyond 64 bits, which are written as strings so that no
+  digit is lost.
 
   ## Examples
 
@@ -1,12 +1,12 @@
 use bytes::Bytes;
-use pact_consumer::builders::MessageInteractionBuilder;
+use pact_consumer::{builders::MessageInteractionBuilder, prelude::JsonPattern};
 use rustler::{NifResult, NifStruct, Resource, ResourceArc};
 use std::sync::Mutex;
 
 use crate::{
     impl_builder_nif,
     models::interaction::{InteractionResource, NifInteraction},
-    patterns::NifJsonPattern,
+    patterns::{NifJsonPattern, add_body_generators},
 };
 
 #[derive(NifStruct)]
@@ -74,6 +74,26 @@ pub fn body(
     Ok(builder)
 }
 
+#[rustler::nif(name = "message_builder_json_body")]
+pub fn json_body(
+    builder: NifMessageInteractionBuilder,
+    body: NifJsonPattern,
+) -> NifResult<NifMessageInteractionBuilder> {
+    builder.invoke(|b| {
+        let generators = body.generators()?;
+        b.json_body(JsonPattern::try_from(body)?);
+        add_body_generators(
+            b.message_contents
+                .generators
+                .get_or_insert_with(Default::default),
+            generators,
+        );
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
 #[rustler::nif(name = "message_builder_build")]
 pub fn build(builder: NifMessageInteractionBuilder) -> NifResult<NifInteraction> {
     let interaction = builder.invoke(|b| Ok(b.build()))?;
@@ -88,4 +108,3 @@ impl_builder_nif!("message_builder_pending", NifMessageInteractionBuilder, pendi
 impl_builder_nif!("message_builder_given", NifMessageInteractionBuilder, given(value: String));
 impl_builder_nif!("message_builder_comment", NifMessageInteractionBuilder, comment(value: String));
 impl_builder_nif!("message_builder_test_name", NifMessageInteractionBuilder, test_name(name: String));
-impl_builder_nif!("message_builder_json_body", NifMessageInteractionBuilder, json_body(body: NifJsonPattern));
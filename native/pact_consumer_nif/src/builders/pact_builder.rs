@@ -1,11 +1,13 @@
 use pact_consumer::prelude::PactBuilderAsync;
 use rustler::{NifResult, NifStruct, Resource, ResourceArc};
-use std::sync::Mutex;
-use tokio::runtime::Runtime;
-
-use crate::models::{
-    interaction::NifInteraction,
-    v4::async_message::{AsynchronousMessageResource, NifAsynchronousMessage},
+use std::{env, path::PathBuf, sync::Mutex};
+
+use crate::{
+    models::{
+        interaction::NifInteraction,
+        v4::async_message::{AsynchronousMessageResource, NifAsynchronousMessage},
+    },
+    runtime,
 };
 
 #[derive(NifStruct)]
@@ -15,23 +17,58 @@ pub struct NifPactBuilder {
     is_v4: bool,
 }
 
-pub struct PactBuilderResource(Mutex<PactBuilderAsync>);
+pub struct PactBuilderResource {
+    builder: Mutex<PactBuilderAsync>,
+    pact_file: Mutex<PactFileOptions>,
+}
+
+/// Where and how pact files are written. Settings made on the builder take
+/// precedence over the `PACT_OUTPUT_DIR` and `PACT_OVERWRITE` environment
+/// variables, which are shared by every test in the VM.
+#[derive(Clone, Default)]
+pub struct PactFileOptions {
+    output_dir: Option<PathBuf>,
+    overwrite: Option<bool>,
+}
+
+impl PactFileOptions {
+    pub fn output_dir(&self) -> PathBuf {
+        self.output_dir
+            .clone()
+            .or_else(|| env::var_os("PACT_OUTPUT_DIR").map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from("target/pacts"))
+    }
+
+    pub fn overwrite(&self) -> bool {
+        self.overwrite
+            .unwrap_or_else(|| env::var("PACT_OVERWRITE").is_ok_and(|v| v == "true"))
+    }
+}
+
+impl PactBuilderResource {
+    fn new(builder: PactBuilderAsync) -> Self {
+        Self {
+            builder: Mutex::new(builder),
+            pact_file: Mutex::new(PactFileOptions::default()),
+        }
+    }
+}
 
 impl NifPactBuilder {
     fn new(consumer: String, provider: String) -> Self {
         Self {
-            inner: ResourceArc::new(PactBuilderResource(Mutex::new(PactBuilderAsync::new(
+            inner: ResourceArc::new(PactBuilderResource::new(PactBuilderAsync::new(
                 consumer, provider,
-            )))),
+            ))),
             is_v4: false,
         }
     }
 
     fn new_v4(consumer: String, provider: String) -> Self {
         Self {
-            inner: ResourceArc::new(PactBuilderResource(Mutex::new(PactBuilderAsync::new_v4(
+            inner: ResourceArc::new(PactBuilderResource::new(PactBuilderAsync::new_v4(
                 consumer, provider,
-            )))),
+            ))),
             is_v4: true,
         }
     }
@@ -44,7 +81,7 @@ impl NifPactBuilder {
     {
         let mut inner = self
             .inner
-            .0
+            .builder
             .lock()
             .map_err(|_e| rustler::Error::RaiseAtom("invalid_pact_builder_reference"))?;
 
@@ -57,12 +94,33 @@ impl NifPactBuilder {
     {
         let mut inner = self
             .inner
-            .0
+            .builder
+            .lock()
+            .map_err(|_e| rustler::Error::RaiseAtom("invalid_pact_builder_reference"))?;
+
+        runtime::block_on(fun(&mut inner))
+    }
+
+    fn update_pact_file<F>(&self, fun: F) -> NifResult<()>
+    where
+        F: FnOnce(&mut PactFileOptions),
+    {
+        let mut pact_file = self
+            .inner
+            .pact_file
             .lock()
             .map_err(|_e| rustler::Error::RaiseAtom("invalid_pact_builder_reference"))?;
 
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async { fun(&mut inner).await })
+        fun(&mut pact_file);
+        Ok(())
+    }
+
+    pub fn pact_file(&self) -> NifResult<PactFileOptions> {
+        self.inner
+            .pact_file
+            .lock()
+            .map(|pact_file| pact_file.clone())
+            .map_err(|_e| rustler::Error::RaiseAtom("invalid_pact_builder_reference"))
     }
 }
 
@@ -120,3 +178,38 @@ fn using_plugin(
 
     Ok(builder)
 }
+
+#[rustler::nif(name = "pact_builder_output_dir")]
+fn output_dir(builder: NifPactBuilder, dir: String) -> NifResult<NifPactBuilder> {
+    builder.invoke(|b| {
+        b.output_dir(&dir);
+        Ok(())
+    })?;
+    builder.update_pact_file(|pact_file| pact_file.output_dir = Some(PathBuf::from(dir)))?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "pact_builder_overwrite")]
+fn overwrite(builder: NifPactBuilder, value: bool) -> NifResult<NifPactBuilder> {
+    builder.update_pact_file(|pact_file| pact_file.overwrite = Some(value))?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "pact_builder_write_pact", schedule = "DirtyIo")]
+fn write_pact(builder: NifPactBuilder) -> NifResult<Result<String, String>> {
+    let pact_file = builder.pact_file()?;
+
+    builder.invoke(|b| {
+        let pact = b.build();
+        let path = pact_file.output_dir().join(pact.default_file_name());
+        let specification = pact.specification_version();
+
+        Ok(
+            pact_models::pact::write_pact(pact, &path, specification, pact_file.overwrite())
+                .map(|_| path.to_string_lossy().into_owned())
+                .map_err(|e| e.to_string()),
+        )
+    })
+}
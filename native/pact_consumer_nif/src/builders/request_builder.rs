@@ -4,12 +4,15 @@ use crate::{
         request::{NifRequest, RequestResource},
         v4::http_parts::{HttpRequestResource, NifHttpRequest},
     },
-    patterns::{NifJsonPattern, NifStringPattern},
+    patterns::{NifJsonPattern, NifStringPattern, add_body_generators, add_header_generator},
+    runtime,
+};
+use pact_consumer::{
+    builders::RequestBuilder,
+    prelude::{HttpPartBuilder, JsonPattern, StringPattern},
 };
-use pact_consumer::{builders::RequestBuilder, prelude::HttpPartBuilder};
 use rustler::{NifResult, NifStruct, Resource, ResourceArc};
 use std::{ops::Deref, sync::Mutex};
-use tokio::runtime::Runtime;
 
 #[derive(NifStruct)]
 #[module = "RequestBuilder"]
@@ -49,8 +52,8 @@ impl NifRequestBuilder {
             .0
             .lock()
             .map_err(|_e| rustler::Error::RaiseAtom("invalid_pact_builder_reference"))?;
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async { fun(&mut inner).await })
+
+        runtime::block_on(fun(&mut inner))
     }
 }
 
@@ -106,6 +109,76 @@ pub fn contents(
     Ok(builder)
 }
 
+#[rustler::nif(name = "request_builder_header")]
+pub fn header(
+    builder: NifRequestBuilder,
+    name: String,
+    value: NifStringPattern,
+) -> NifResult<NifRequestBuilder> {
+    builder.invoke(|b| {
+        let generator = value.generator()?;
+        b.header(&name, StringPattern::try_from(value)?);
+        add_header_generator(b.generators(), &name, generator);
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "request_builder_json_body")]
+pub fn json_body(builder: NifRequestBuilder, body: NifJsonPattern) -> NifResult<NifRequestBuilder> {
+    builder.invoke(|b| {
+        let generators = body.generators()?;
+        b.json_body(JsonPattern::try_from(body)?);
+        add_body_generators(b.generators(), generators);
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "request_builder_header_from_provider_state")]
+pub fn header_from_provider_state(
+    builder: NifRequestBuilder,
+    name: String,
+    expression: String,
+    value: NifStringPattern,
+) -> NifResult<NifRequestBuilder> {
+    builder.invoke(|b| {
+        b.header_from_provider_state(name, expression, StringPattern::try_from(value)?);
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "request_builder_body_matching")]
+pub fn body_matching(
+    builder: NifRequestBuilder,
+    body: NifStringPattern,
+) -> NifResult<NifRequestBuilder> {
+    builder.invoke(|b| {
+        b.body_matching(StringPattern::try_from(body)?);
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "request_builder_body_matching2")]
+pub fn body_matching2(
+    builder: NifRequestBuilder,
+    body: NifStringPattern,
+    content_type: String,
+) -> NifResult<NifRequestBuilder> {
+    builder.invoke(|b| {
+        b.body_matching2(StringPattern::try_from(body)?, content_type);
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
 impl_builder_nif!("request_builder_method", NifRequestBuilder, method(value: String));
 impl_builder_nif!("request_builder_get", NifRequestBuilder, get());
 impl_builder_nif!("request_builder_post", NifRequestBuilder, post());
@@ -114,13 +187,8 @@ impl_builder_nif!("request_builder_delete", NifRequestBuilder, delete());
 impl_builder_nif!("request_builder_path", NifRequestBuilder, path(value: String));
 impl_builder_nif!("request_builder_path_from_provider_state", NifRequestBuilder, path_from_provider_state(expression: String, value: String));
 impl_builder_nif!("request_builder_query_param", NifRequestBuilder, query_param(key: String, value: String));
-impl_builder_nif!("request_builder_header", NifRequestBuilder, header(name: String, value: NifStringPattern));
-impl_builder_nif!("request_builder_header_from_provider_state", NifRequestBuilder, header_from_provider_state(name: String, expression: String, value: NifStringPattern));
 impl_builder_nif!("request_builder_content_type", NifRequestBuilder, content_type(value: String));
 impl_builder_nif!("request_builder_html", NifRequestBuilder, html());
 impl_builder_nif!("request_builder_json_utf8", NifRequestBuilder, json_utf8());
 impl_builder_nif!("request_builder_body", NifRequestBuilder, body(value: String));
 impl_builder_nif!("request_builder_body2", NifRequestBuilder, body2(body: String, content_type: String));
-impl_builder_nif!("request_builder_json_body", NifRequestBuilder, json_body(body: NifJsonPattern));
-impl_builder_nif!("request_builder_body_matching", NifRequestBuilder, body_matching(body: NifStringPattern));
-impl_builder_nif!("request_builder_body_matching2", NifRequestBuilder, body_matching2(body: NifStringPattern, content_type: String));
@@ -4,12 +4,20 @@ use crate::{
         response::{NifResponse, ResponseResource},
         v4::http_parts::{HttpResponseResource, NifHttpResponse},
     },
-    patterns::NifStringPattern,
+    patterns::{NifJsonPattern, NifStringPattern, add_body_generators, add_header_generator},
+    runtime,
 };
-use pact_consumer::{builders::ResponseBuilder, prelude::HttpPartBuilder};
-use rustler::{NifResult, NifStruct, Resource, ResourceArc};
+use pact_consumer::{
+    builders::ResponseBuilder,
+    prelude::{HttpPartBuilder, JsonPattern, StringPattern},
+};
+use pact_models::{
+    HttpStatus,
+    matchingrules::{MatchingRule, RuleLogic},
+    path_exp::DocPath,
+};
+use rustler::{NifResult, NifStruct, NifUnitEnum, NifUntaggedEnum, Resource, ResourceArc};
 use std::{ops::Deref, sync::Mutex};
-use tokio::runtime::Runtime;
 
 #[derive(NifStruct)]
 #[module = "ResponseBuilder"]
@@ -50,8 +58,8 @@ impl NifResponseBuilder {
             .0
             .lock()
             .map_err(|_e| rustler::Error::RaiseAtom("invalid_pact_builder_reference"))?;
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async { fun(&mut inner).await })
+
+        runtime::block_on(fun(&mut inner))
     }
 }
 
@@ -107,6 +115,127 @@ pub fn contents(
     Ok(builder)
 }
 
+#[derive(NifUnitEnum)]
+pub enum NifStatusClass {
+    Information,
+    Success,
+    Redirect,
+    ClientError,
+    ServerError,
+    NonError,
+    Error,
+}
+
+#[derive(NifUntaggedEnum)]
+pub enum NifHttpStatus {
+    Class(NifStatusClass),
+    Codes(Vec<u16>),
+}
+
+impl From<NifHttpStatus> for HttpStatus {
+    fn from(value: NifHttpStatus) -> Self {
+        match value {
+            NifHttpStatus::Class(NifStatusClass::Information) => HttpStatus::Information,
+            NifHttpStatus::Class(NifStatusClass::Success) => HttpStatus::Success,
+            NifHttpStatus::Class(NifStatusClass::Redirect) => HttpStatus::Redirect,
+            NifHttpStatus::Class(NifStatusClass::ClientError) => HttpStatus::ClientError,
+            NifHttpStatus::Class(NifStatusClass::ServerError) => HttpStatus::ServerError,
+            NifHttpStatus::Class(NifStatusClass::NonError) => HttpStatus::NonError,
+            NifHttpStatus::Class(NifStatusClass::Error) => HttpStatus::Error,
+            NifHttpStatus::Codes(codes) => HttpStatus::StatusCodes(codes),
+        }
+    }
+}
+
+/// The status codes accepted for `status`, and the one to use as the example
+/// when the current status code is not accepted.
+fn accepted_statuses(status: &HttpStatus) -> (Vec<u16>, u16) {
+    let (codes, example) = match status {
+        HttpStatus::Information => (100..200, 100),
+        HttpStatus::Success => (200..300, 200),
+        HttpStatus::Redirect => (300..400, 301),
+        HttpStatus::ClientError => (400..500, 400),
+        HttpStatus::ServerError => (500..600, 500),
+        HttpStatus::NonError => (100..400, 200),
+        HttpStatus::Error => (400..600, 400),
+        HttpStatus::StatusCodes(codes) => {
+            return (codes.clone(), codes.first().copied().unwrap_or(200));
+        }
+    };
+    (codes.collect(), example)
+}
+
+#[rustler::nif(name = "response_builder_status_matching")]
+pub fn status_matching(
+    builder: NifResponseBuilder,
+    status: NifHttpStatus,
+) -> NifResult<NifResponseBuilder> {
+    let status = HttpStatus::from(status);
+    builder.invoke(|b| {
+        let (accepted, example) = accepted_statuses(&status);
+        if !accepted.contains(&b.build_v4().status) {
+            b.status(example);
+        }
+        b.body_and_matching_rules_mut()
+            .1
+            .add_category("status")
+            .add_rule(
+                DocPath::empty(),
+                MatchingRule::StatusCode(status),
+                RuleLogic::And,
+            );
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "response_builder_header")]
+pub fn header(
+    builder: NifResponseBuilder,
+    name: String,
+    value: NifStringPattern,
+) -> NifResult<NifResponseBuilder> {
+    builder.invoke(|b| {
+        let generator = value.generator()?;
+        b.header(&name, StringPattern::try_from(value)?);
+        add_header_generator(b.generators(), &name, generator);
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "response_builder_json_body")]
+pub fn json_body(
+    builder: NifResponseBuilder,
+    body: NifJsonPattern,
+) -> NifResult<NifResponseBuilder> {
+    builder.invoke(|b| {
+        let generators = body.generators()?;
+        b.json_body(JsonPattern::try_from(body)?);
+        add_body_generators(b.generators(), generators);
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
+#[rustler::nif(name = "response_builder_header_from_provider_state")]
+pub fn header_from_provider_state(
+    builder: NifResponseBuilder,
+    name: String,
+    expression: String,
+    value: NifStringPattern,
+) -> NifResult<NifResponseBuilder> {
+    builder.invoke(|b| {
+        b.header_from_provider_state(name, expression, StringPattern::try_from(value)?);
+        Ok(())
+    })?;
+
+    Ok(builder)
+}
+
 impl_builder_nif!("response_builder_status", NifResponseBuilder, status(value: u16));
 impl_builder_nif!("response_builder_ok", NifResponseBuilder, ok());
 impl_builder_nif!("response_builder_created", NifResponseBuilder, created());
@@ -125,8 +254,6 @@ impl_builder_nif!(
     NifResponseBuilder,
     not_found()
 );
-impl_builder_nif!("response_builder_header", NifResponseBuilder, header(name: String, value: NifStringPattern));
-impl_builder_nif!("response_builder_header_from_provider_state", NifResponseBuilder, header_from_provider_state(name: String, expression: String, value: NifStringPattern));
 impl_builder_nif!("response_builder_content_type", NifResponseBuilder, content_type(value: String));
 impl_builder_nif!("response_builder_html", NifResponseBuilder, html());
 impl_builder_nif!(
@@ -136,6 +263,5 @@ impl_builder_nif!(
 );
 impl_builder_nif!("response_builder_body", NifResponseBuilder, body(value: String));
 impl_builder_nif!("response_builder_body2", NifResponseBuilder, body2(body: String, content_type: String));
-impl_builder_nif!("response_builder_json_body", NifResponseBuilder, json_body(body: String));
 impl_builder_nif!("response_builder_body_matching", NifResponseBuilder, body_matching(body: String));
 impl_builder_nif!("response_builder_body_matching2", NifResponseBuilder, body_matching2(body: String, content_type: String));
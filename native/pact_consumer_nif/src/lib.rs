@@ -2,6 +2,7 @@ mod builders;
 mod mock_server;
 mod models;
 mod patterns;
+mod runtime;
 
 use builders::{
     interaction_builder::InteractionBuilderResource,
@@ -26,6 +27,7 @@ fn on_load(env: Env, _: Term) -> bool {
         env::set_var("RUST_BACKTRACE", "1");
     }
     env_logger::init();
+    runtime::runtime();
 
     std::panic::set_hook(Box::new(|panic_info| {
         let backtrace = std::backtrace::Backtrace::force_capture();
@@ -1,18 +1,45 @@
 use std::{
+    collections::HashMap,
     ops::Deref,
+    path::PathBuf,
     sync::{
         Mutex,
-        mpsc::{Receiver, SyncSender, sync_channel},
+        mpsc::{Receiver, Sender, SyncSender, channel, sync_channel},
     },
 };
 
-use pact_consumer::prelude::StartMockServer;
-use rustler::{Env, NifResult, NifStruct, Resource, ResourceArc};
+use pact_consumer::prelude::{PactBuilderAsync, ValidatingMockServer};
+use pact_mock_server::{
+    matching::MatchResult,
+    mock_server::{MockServerConfig, MockServerMetrics},
+};
+use pact_models::{plugins::PluginData, v4::synch_http::SynchronousHttp};
+use pact_plugin_driver::plugin_models::{PluginDependency, PluginDependencyType};
+use rustler::{
+    Env, LocalPid, NifMap, NifResult, NifStruct, NifTaggedEnum, NifUntaggedEnum, Resource,
+    ResourceArc,
+};
+use tokio::task::JoinHandle;
 
-use crate::builders::pact_builder::NifPactBuilder;
+use crate::{
+    builders::pact_builder::{NifPactBuilder, PactFileOptions},
+    models::{
+        match_result::{NifMatchResult, NifPluginMismatch},
+        received_request::NifReceivedRequest,
+    },
+    runtime,
+};
 
-pub struct CommandChannel(Mutex<SyncSender<Command>>);
-pub struct ResponseChannel(Mutex<Receiver<String>>);
+mod http_mock_server;
+mod plugin_mock_server;
+mod request_notifier;
+
+use http_mock_server::ValidatingHttpMockServer;
+use plugin_mock_server::PluginMockServer;
+
+/// Carries each command to the mock server thread along with the channel to
+/// reply on, so that concurrent callers never see each other's responses.
+pub struct CommandChannel(Sender<(Command, SyncSender<Response>)>);
 
 #[derive(NifStruct)]
 #[module = "ValidatingMockServer"]
@@ -30,81 +57,427 @@ impl Deref for NifValidatingMockServer {
 
 pub struct ValidatingMockServerResource {
     command_tx: CommandChannel,
-    response_rx: ResponseChannel,
+    thread: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl ValidatingMockServerResource {
-    fn send_command(&self, command: Command) -> NifResult<()> {
+    fn send_command(&self, command: Command) -> NifResult<Receiver<Response>> {
+        let (reply_tx, reply_rx) = sync_channel(1);
         self.command_tx
             .0
-            .lock()
-            .map_err(|_e| rustler::error::Error::RaiseAtom("unable_lock_command_channel"))?
-            .send(command)
-            .map_err(|_e| rustler::error::Error::RaiseAtom("unable_send_command"))
+            .send((command, reply_tx))
+            .map_err(|_e| rustler::error::Error::RaiseAtom("unable_send_command"))?;
+        Ok(reply_rx)
     }
 
-    fn receive_response(&self) -> NifResult<String> {
-        self.response_rx
-            .0
-            .lock()
-            .map_err(|_e| rustler::error::Error::RaiseAtom("unable_lock_response_channel"))?
+    fn call(&self, command: Command) -> NifResult<Response> {
+        self.send_command(command)?
             .recv()
             .map_err(|_e| rustler::error::Error::RaiseAtom("unable_receive_response"))
     }
+
+    fn join(&self) -> NifResult<()> {
+        let thread = self
+            .thread
+            .lock()
+            .map_err(|_e| rustler::error::Error::RaiseAtom("unable_lock_thread"))?
+            .take();
+        if let Some(thread) = thread {
+            runtime::block_on(thread)
+                .map_err(|_e| rustler::error::Error::RaiseAtom("unable_join_thread"))?;
+        }
+        Ok(())
+    }
 }
 
 impl Resource for ValidatingMockServerResource {
     fn destructor(self, _env: Env<'_>) {
-        self.send_command(Command::Stop).expect("thread running");
+        // The thread is gone already if the mock server was stopped explicitly.
+        let _ = self.send_command(Command::Stop);
+    }
+}
+
+#[derive(NifTaggedEnum)]
+#[rustler(encode)]
+pub enum NifVerificationResult {
+    Ok,
+    Error(NifMockServerError),
+}
+
+/// Why a mock server failed verification or did not write its pact when it
+/// was stopped: either the requests that were missing or did not match, or
+/// the error that occurred.
+#[derive(NifUntaggedEnum)]
+#[rustler(encode)]
+pub enum NifMockServerError {
+    Mismatches(Vec<NifMatchResult>),
+    Reason(String),
+}
+
+/// The outcome of shutting a mock server down.
+enum Shutdown {
+    /// Every expected request was received and matched, and the pact was
+    /// written to this file.
+    PactWritten(PathBuf),
+    /// The requests that were missing or did not match.
+    Mismatches(Vec<MatchResult>),
+    /// The failures reported by the plugin behind the mock server.
+    PluginMismatches(Vec<NifPluginMismatch>),
+}
+
+#[derive(NifMap)]
+#[rustler(encode)]
+pub struct NifMockServerMetrics {
+    requests: usize,
+    requests_by_path: HashMap<String, usize>,
+}
+
+impl From<MockServerMetrics> for NifMockServerMetrics {
+    fn from(metrics: MockServerMetrics) -> Self {
+        Self {
+            requests: metrics.requests,
+            requests_by_path: metrics.requests_by_path,
+        }
     }
 }
 
 #[rustler::nif(name = "mock_server_url")]
 pub fn url(mock_server: NifValidatingMockServer) -> NifResult<String> {
-    mock_server.send_command(Command::Url)?;
-    mock_server.receive_response()
+    match mock_server.call(Command::Url)? {
+        Response::Url(url) => Ok(url),
+        _ => Err(rustler::error::Error::RaiseAtom("unexpected_response")),
+    }
 }
 
 #[rustler::nif(name = "mock_server_path")]
 pub fn path(mock_server: NifValidatingMockServer, value: String) -> NifResult<String> {
-    mock_server.send_command(Command::Path(value))?;
-    mock_server.receive_response()
+    match mock_server.call(Command::Path(value))? {
+        Response::Url(url) => Ok(url),
+        _ => Err(rustler::error::Error::RaiseAtom("unexpected_response")),
+    }
+}
+
+#[rustler::nif(name = "mock_server_certificate")]
+pub fn certificate(mock_server: NifValidatingMockServer) -> NifResult<Option<String>> {
+    match mock_server.call(Command::Certificate)? {
+        Response::Certificate(certificate) => Ok(certificate),
+        _ => Err(rustler::error::Error::RaiseAtom("unexpected_response")),
+    }
+}
+
+#[rustler::nif(name = "mock_server_metrics")]
+pub fn metrics(mock_server: NifValidatingMockServer) -> NifResult<NifMockServerMetrics> {
+    match mock_server.call(Command::Metrics)? {
+        Response::Metrics(metrics) => Ok(metrics.into()),
+        _ => Err(rustler::error::Error::RaiseAtom("unexpected_response")),
+    }
+}
+
+#[rustler::nif(name = "mock_server_received_requests")]
+pub fn received_requests(
+    mock_server: NifValidatingMockServer,
+) -> NifResult<Vec<NifReceivedRequest>> {
+    match mock_server.call(Command::ReceivedRequests)? {
+        Response::ReceivedRequests(requests) => Ok(requests),
+        _ => Err(rustler::error::Error::RaiseAtom("unexpected_response")),
+    }
+}
+
+#[rustler::nif(name = "mock_server_verify", schedule = "DirtyIo")]
+pub fn verify(mock_server: NifValidatingMockServer) -> NifResult<NifVerificationResult> {
+    match mock_server.call(Command::Verify)? {
+        Response::Verified(Ok(mismatches)) if mismatches.is_empty() => {
+            Ok(NifVerificationResult::Ok)
+        }
+        Response::Verified(Ok(mismatches)) => Ok(NifVerificationResult::Error(
+            NifMockServerError::Mismatches(mismatches),
+        )),
+        Response::Verified(Err(reason)) => Ok(NifVerificationResult::Error(
+            NifMockServerError::Reason(reason),
+        )),
+        _ => Err(rustler::error::Error::RaiseAtom("unexpected_response")),
+    }
+}
+
+#[rustler::nif(name = "mock_server_stop", schedule = "DirtyIo")]
+pub fn stop(mock_server: NifValidatingMockServer) -> NifResult<Result<String, NifMockServerError>> {
+    let response = mock_server.call(Command::Stop)?;
+    mock_server.join()?;
+    match response {
+        Response::Stopped(result) => Ok(result),
+        _ => Err(rustler::error::Error::RaiseAtom("unexpected_response")),
+    }
 }
 
 enum Command {
     Url,
     Path(String),
+    Certificate,
+    Metrics,
+    ReceivedRequests,
+    Verify,
     Stop,
 }
 
-#[rustler::nif(name = "mock_server_start")]
-pub fn start(builder: NifPactBuilder) -> NifResult<NifValidatingMockServer> {
-    let (command_tx, command_rx) = sync_channel::<Command>(1);
-    let (response_tx, response_rx) = sync_channel::<String>(1);
-
-    std::thread::spawn(move || {
-        let mock_server = builder
-            .invoke(|b| Ok(b.start_mock_server(None, None)))
-            .map_err(|_e| "Unable to start mock server".to_string())?;
-
-        while let Ok(cmd) = command_rx.recv() {
-            let response = match cmd {
-                Command::Url => mock_server.url().to_string(),
-                Command::Path(path) => mock_server.path(&path).to_string(),
-                Command::Stop => break,
-            };
-            if let Err(e) = response_tx.send(response) {
-                eprintln!("failed to send response: {}", e);
+enum Response {
+    Url(String),
+    Certificate(Option<String>),
+    Metrics(MockServerMetrics),
+    ReceivedRequests(Vec<NifReceivedRequest>),
+    Verified(Result<Vec<NifMatchResult>, String>),
+    Stopped(Result<String, NifMockServerError>),
+}
+
+#[derive(NifMap)]
+pub struct NifMockServerOptions {
+    port: Option<u16>,
+    host: Option<String>,
+    transport: Option<String>,
+    cors_preflight: bool,
+    tls: bool,
+    owner: Option<LocalPid>,
+}
+
+impl NifMockServerOptions {
+    /// The address to bind to, if either the host or the port was given.
+    /// Otherwise the mock server picks a free port on the loopback interface.
+    fn address(&self) -> String {
+        if self.host.is_none() && self.port.is_none() {
+            return String::new();
+        }
+
+        let host = self.host.as_deref().unwrap_or("127.0.0.1");
+        let port = self.port.unwrap_or(0);
+        if host.contains(':') && !host.starts_with('[') {
+            format!("[{}]:{}", host, port)
+        } else {
+            format!("{}:{}", host, port)
+        }
+    }
+
+    fn config(&self) -> MockServerConfig {
+        MockServerConfig {
+            cors_preflight: self.cors_preflight,
+            address: self.address(),
+            ..MockServerConfig::default()
+        }
+    }
+}
+
+fn plugin_dependency(plugin: &PluginData) -> PluginDependency {
+    PluginDependency {
+        name: plugin.name.clone(),
+        version: Some(plugin.version.clone()),
+        dependency_type: PluginDependencyType::Plugin,
+    }
+}
+
+/// The transport to start a mock server for: the one asked for, or else the
+/// first non-HTTP transport set on an interaction, e.g. `grpc` for
+/// interactions defined with the protobuf plugin.
+fn transport(b: &PactBuilderAsync, options: &NifMockServerOptions) -> Option<String> {
+    options.transport.clone().or_else(|| {
+        b.build()
+            .as_v4_pact()
+            .ok()?
+            .interactions
+            .iter()
+            .filter_map(|i| i.transport())
+            .find(|t| !is_http(t))
+    })
+}
+
+fn is_http(transport: &str) -> bool {
+    transport == "http" || transport == "https"
+}
+
+/// The mock servers that can be started: HTTP(S) servers run directly on
+/// `pact_mock_server`, while servers for other transports are hosted by the
+/// plugin providing the transport.
+enum MockServerHandle {
+    Http(Box<ValidatingHttpMockServer>),
+    Transport(Box<PluginMockServer>),
+}
+
+impl Deref for MockServerHandle {
+    type Target = dyn ValidatingMockServer;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            MockServerHandle::Http(mock_server) => mock_server.as_ref(),
+            MockServerHandle::Transport(mock_server) => mock_server.as_ref(),
+        }
+    }
+}
+
+impl MockServerHandle {
+    /// The requests that were missing or did not match so far. Plugins are
+    /// asked for their results, which may fail.
+    fn verify(&self, interactions: &[SynchronousHttp]) -> anyhow::Result<Vec<NifMatchResult>> {
+        match self {
+            MockServerHandle::Http(mock_server) => Ok(mock_server
+                .status()
+                .into_iter()
+                .map(|result| NifMatchResult::new(result, interactions))
+                .collect()),
+            MockServerHandle::Transport(mock_server) => Ok(mock_server
+                .results()?
+                .into_iter()
+                .map(NifMatchResult::PluginMismatch)
+                .collect()),
+        }
+    }
+
+    fn stop(&mut self) -> anyhow::Result<Shutdown> {
+        match self {
+            MockServerHandle::Http(mock_server) => mock_server.stop(),
+            MockServerHandle::Transport(mock_server) => mock_server.stop(),
+        }
+    }
+}
+
+/// A started mock server, along with what is needed to answer commands about it.
+struct RunningMockServer {
+    mock_server: MockServerHandle,
+    interactions: Vec<SynchronousHttp>,
+}
+
+impl RunningMockServer {
+    fn start(
+        b: &PactBuilderAsync,
+        options: &NifMockServerOptions,
+        pact_file: PactFileOptions,
+    ) -> Result<Self, String> {
+        let interactions = b
+            .build()
+            .interactions()
+            .iter()
+            .filter_map(|i| i.as_v4_http())
+            .collect::<Vec<_>>();
+
+        let mock_server = match transport(b, options) {
+            Some(transport) if !is_http(&transport) => PluginMockServer::start(
+                b.build(),
+                &transport,
+                options.host.clone(),
+                options.port,
+                options.tls,
+                options.config(),
+                pact_file,
+            )
+            .map(|mock_server| MockServerHandle::Transport(Box::new(mock_server))),
+            transport => ValidatingHttpMockServer::start(
+                b.build(),
+                options.config(),
+                options.tls || transport.as_deref() == Some("https"),
+                pact_file,
+                options.owner,
+            )
+            .map(|mock_server| MockServerHandle::Http(Box::new(mock_server))),
+        }
+        .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            mock_server,
+            interactions,
+        })
+    }
+
+    fn handle(&mut self, cmd: Command) -> Response {
+        let mock_server = &self.mock_server;
+        match cmd {
+            Command::Url => Response::Url(mock_server.url().to_string()),
+            Command::Path(path) => Response::Url(mock_server.path(&path).to_string()),
+            Command::Certificate => Response::Certificate(match mock_server {
+                MockServerHandle::Http(mock_server) => {
+                    mock_server.certificate().map(ToString::to_string)
+                }
+                MockServerHandle::Transport(_) => None,
+            }),
+            Command::Metrics => Response::Metrics(mock_server.metrics()),
+            Command::ReceivedRequests => Response::ReceivedRequests(match mock_server {
+                MockServerHandle::Http(mock_server) => mock_server
+                    .matches()
+                    .into_iter()
+                    .filter_map(|result| NifReceivedRequest::new(result, &self.interactions))
+                    .collect(),
+                MockServerHandle::Transport(_) => Vec::new(),
+            }),
+            Command::Verify => Response::Verified(
+                mock_server
+                    .verify(&self.interactions)
+                    .map_err(|e| e.to_string()),
+            ),
+            Command::Stop => Response::Stopped(self.stop()),
+        }
+    }
+
+    fn stop(&mut self) -> Result<String, NifMockServerError> {
+        match self.mock_server.stop() {
+            Ok(Shutdown::PactWritten(path)) => Ok(path.to_string_lossy().into_owned()),
+            Ok(Shutdown::Mismatches(mismatches)) => Err(NifMockServerError::Mismatches(
+                mismatches
+                    .into_iter()
+                    .map(|result| NifMatchResult::new(result, &self.interactions))
+                    .collect(),
+            )),
+            Ok(Shutdown::PluginMismatches(mismatches)) => Err(NifMockServerError::Mismatches(
+                mismatches
+                    .into_iter()
+                    .map(NifMatchResult::PluginMismatch)
+                    .collect(),
+            )),
+            Err(e) => Err(NifMockServerError::Reason(e.to_string())),
+        }
+    }
+}
+
+#[rustler::nif(name = "mock_server_start", schedule = "DirtyIo")]
+pub fn start(
+    builder: NifPactBuilder,
+    options: NifMockServerOptions,
+) -> Result<NifValidatingMockServer, String> {
+    let (startup_tx, startup_rx) = sync_channel::<Result<(), String>>(1);
+    let (command_tx, command_rx) = channel::<(Command, SyncSender<Response>)>();
+
+    let thread = runtime::runtime().spawn_blocking(move || {
+        let started = builder
+            .pact_file()
+            .and_then(|pact_file| {
+                builder.invoke(|b| Ok(RunningMockServer::start(b, &options, pact_file)))
+            })
+            .map_err(|_e| "Unable to start mock server".to_string())
+            .and_then(|started| started);
+
+        let mut running = match started {
+            Ok(running) => {
+                let _ = startup_tx.send(Ok(()));
+                running
+            }
+            Err(reason) => {
+                let _ = startup_tx.send(Err(reason));
+                return;
+            }
+        };
+
+        while let Ok((cmd, reply_tx)) = command_rx.recv() {
+            let stop = matches!(cmd, Command::Stop);
+            // The caller may have gone away, e.g. when stopped by the destructor.
+            let _ = reply_tx.send(running.handle(cmd));
+            if stop {
                 break;
             }
         }
-        Ok::<(), String>(())
     });
 
+    startup_rx
+        .recv()
+        .map_err(|_e| "Unable to start mock server".to_string())??;
+
     Ok(NifValidatingMockServer {
         inner: ValidatingMockServerResource {
-            command_tx: CommandChannel(Mutex::new(command_tx)),
-            response_rx: ResponseChannel(Mutex::new(response_rx)),
+            command_tx: CommandChannel(command_tx),
+            thread: Mutex::new(Some(thread)),
         }
         .into(),
     })
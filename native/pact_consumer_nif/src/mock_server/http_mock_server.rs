@@ -0,0 +1,157 @@
+use pact_consumer::prelude::ValidatingMockServer;
+use pact_mock_server::{
+    builder::MockServerBuilder,
+    matching::MatchResult,
+    mock_server::{MockServer, MockServerConfig, MockServerMetrics},
+};
+use pact_models::pact::{Pact, ReadWritePact};
+use pact_plugin_driver::plugin_manager::{drop_plugin_access, increment_plugin_access};
+use rcgen::{CertifiedKey, generate_simple_self_signed};
+use rustler::LocalPid;
+use url::Url;
+
+use super::{Shutdown, plugin_dependency, request_notifier::RequestNotifier};
+use crate::{builders::pact_builder::PactFileOptions, runtime};
+
+/// A mock HTTP(S) server built directly on `pact_mock_server`. It mirrors
+/// `pact_consumer`'s `ValidatingHttpMockServer`, but keeps access to the
+/// underlying server so the requests it received can be inspected, and can
+/// serve HTTPS with a freshly generated self-signed certificate.
+pub struct ValidatingHttpMockServer {
+    url: Url,
+    mock_server: MockServer,
+    certificate: Option<String>,
+    pact_file: PactFileOptions,
+    running: bool,
+    // Declared last so it is dropped after the mock server has shut down.
+    _notifier: Option<RequestNotifier>,
+}
+
+impl ValidatingHttpMockServer {
+    pub fn start(
+        pact: Box<dyn Pact + Send + Sync>,
+        config: MockServerConfig,
+        tls: bool,
+        pact_file: PactFileOptions,
+        owner: Option<LocalPid>,
+    ) -> anyhow::Result<Self> {
+        let plugins = pact.plugin_data();
+        let mut builder = MockServerBuilder::new().with_config(config).with_pact(pact);
+        if !builder.address_assigned() {
+            builder = builder.bind_to_ip4_port(0);
+        }
+
+        let certificate = if tls {
+            let CertifiedKey { cert, signing_key } = generate_simple_self_signed(vec![
+                "localhost".to_string(),
+                "127.0.0.1".to_string(),
+            ])?;
+            let certificate = cert.pem();
+            builder = builder.with_tls_certs(&certificate, &signing_key.serialize_pem())?;
+            Some(certificate)
+        } else {
+            None
+        };
+
+        let mock_server = if tls {
+            runtime::block_on(builder.start_https())?
+        } else {
+            runtime::block_on(builder.start())?
+        };
+
+        for plugin in &plugins {
+            increment_plugin_access(&plugin_dependency(plugin));
+        }
+
+        let notifier = owner.map(|owner| RequestNotifier::start(mock_server.clone(), owner));
+
+        Ok(Self {
+            url: mock_server.url().parse()?,
+            mock_server,
+            certificate,
+            pact_file,
+            running: true,
+            _notifier: notifier,
+        })
+    }
+
+    /// The PEM encoded self-signed certificate the mock server presents, if
+    /// it serves HTTPS.
+    pub fn certificate(&self) -> Option<&str> {
+        self.certificate.as_deref()
+    }
+
+    /// Every request received so far, in order, with its match result.
+    pub fn matches(&self) -> Vec<MatchResult> {
+        self.mock_server.matches()
+    }
+
+    /// Shuts the mock server down and, if every expected request was received
+    /// and matched, writes the pact file.
+    pub fn stop(&mut self) -> anyhow::Result<Shutdown> {
+        self.running = false;
+        self.mock_server.shutdown()?;
+
+        for plugin in self.mock_server.pact.plugin_data() {
+            drop_plugin_access(&plugin_dependency(&plugin));
+        }
+
+        let mismatches = self.mock_server.mismatches();
+        if !mismatches.is_empty() {
+            return Ok(Shutdown::Mismatches(mismatches));
+        }
+
+        let output_dir = self.pact_file.output_dir();
+        self.mock_server.write_pact(
+            &Some(output_dir.to_string_lossy().into_owned()),
+            self.pact_file.overwrite(),
+        )?;
+        Ok(Shutdown::PactWritten(
+            output_dir.join(self.mock_server.pact.default_file_name()),
+        ))
+    }
+}
+
+impl ValidatingMockServer for ValidatingHttpMockServer {
+    fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    fn path(&self, path: &str) -> Url {
+        self.url.join(path).expect("could not parse URL")
+    }
+
+    fn status(&self) -> Vec<MatchResult> {
+        self.mock_server.mismatches()
+    }
+
+    fn metrics(&self) -> MockServerMetrics {
+        self.mock_server.metrics.lock().unwrap().clone()
+    }
+}
+
+impl Drop for ValidatingHttpMockServer {
+    fn drop(&mut self) {
+        if !self.running {
+            return;
+        }
+
+        match self.stop() {
+            Ok(Shutdown::PactWritten(_) | Shutdown::PluginMismatches(_)) => {}
+            Ok(Shutdown::Mismatches(mismatches)) => {
+                let pact = &self.mock_server.pact;
+                log::error!(
+                    "Mock server {}/{} failed verification: {}",
+                    pact.consumer.name,
+                    pact.provider.name,
+                    mismatches
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            Err(e) => log::error!("{}", e),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use pact_consumer::prelude::ValidatingMockServer;
+use pact_matching::Mismatch;
+use pact_mock_server::{
+    matching::MatchResult,
+    mock_server::{MockServerConfig, MockServerMetrics},
+};
+use pact_models::{
+    PactSpecification,
+    pact::{Pact, write_pact},
+};
+use pact_plugin_driver::{
+    catalogue_manager::{self, CatalogueEntry, CatalogueEntryType},
+    mock_server::{self, MockServerDetails, MockServerResults},
+    plugin_manager::{
+        drop_plugin_access, get_mock_server_results, increment_plugin_access, shutdown_mock_server,
+        start_mock_server_v2,
+    },
+};
+use serde_json::Value;
+use url::Url;
+
+use super::{Shutdown, plugin_dependency};
+use crate::{
+    builders::pact_builder::PactFileOptions, models::match_result::NifPluginMismatch, runtime,
+};
+
+/// A mock server for a transport provided by a plugin, e.g. gRPC. It mirrors
+/// `pact_consumer`'s `PluginMockServer`, but reports a missing catalogue entry
+/// or a failing plugin as an error rather than a panic, and writes the pact
+/// file where the builder was told to.
+pub struct PluginMockServer {
+    url: Url,
+    details: MockServerDetails,
+    pact: Box<dyn Pact + Send + Sync>,
+    catalogue_entry: CatalogueEntry,
+    pact_file: PactFileOptions,
+    running: bool,
+}
+
+impl PluginMockServer {
+    pub fn start(
+        pact: Box<dyn Pact + Send + Sync>,
+        transport: &str,
+        host: Option<String>,
+        port: Option<u16>,
+        tls: bool,
+        config: MockServerConfig,
+        pact_file: PactFileOptions,
+    ) -> anyhow::Result<Self> {
+        let catalogue_entry = catalogue_manager::lookup_entry(transport).ok_or_else(|| {
+            anyhow::anyhow!("Did not find a catalogue entry for key '{}'", transport)
+        })?;
+        if catalogue_entry.entry_type != CatalogueEntryType::TRANSPORT {
+            return Err(anyhow::anyhow!(
+                "Catalogue entry for key '{}' is not for a network transport",
+                transport
+            ));
+        }
+
+        let test_context = HashMap::from([(
+            "transport_config".to_string(),
+            Value::Object(config.transport_config.into_iter().collect()),
+        )]);
+        let details = runtime::block_on(start_mock_server_v2(
+            &catalogue_entry,
+            pact.boxed(),
+            mock_server::MockServerConfig {
+                output_path: Some(pact_file.output_dir()),
+                host_interface: host,
+                port: port.map(u32::from).unwrap_or_default(),
+                tls,
+            },
+            test_context,
+        ))?;
+
+        for plugin in &pact.plugin_data() {
+            increment_plugin_access(&plugin_dependency(plugin));
+        }
+
+        Ok(Self {
+            url: details.base_url.parse()?,
+            details,
+            pact,
+            catalogue_entry,
+            pact_file,
+            running: true,
+        })
+    }
+
+    /// The failures the plugin has reported so far, e.g. requests that did not
+    /// match or expected requests that were never received.
+    pub fn results(&self) -> anyhow::Result<Vec<NifPluginMismatch>> {
+        let results = runtime::block_on(get_mock_server_results(&self.details))?;
+        Ok(plugin_mismatches(results))
+    }
+
+    /// Shuts the mock server down and, if the plugin reports no errors or
+    /// mismatches, writes the pact file.
+    pub fn stop(&mut self) -> anyhow::Result<Shutdown> {
+        self.running = false;
+        let results = runtime::block_on(shutdown_mock_server(&self.details))?;
+
+        for plugin in self.pact.plugin_data() {
+            drop_plugin_access(&plugin_dependency(&plugin));
+        }
+
+        let mismatches = plugin_mismatches(results);
+        if !mismatches.is_empty() {
+            return Ok(Shutdown::PluginMismatches(mismatches));
+        }
+
+        self.write_pact().map(Shutdown::PactWritten)
+    }
+
+    fn write_pact(&self) -> anyhow::Result<PathBuf> {
+        let pact = match self.pact.as_v4_pact() {
+            Ok(mut pact) => {
+                for interaction in &mut pact.interactions {
+                    interaction.set_transport(Some(self.catalogue_entry.key.clone()));
+                }
+                pact.boxed()
+            }
+            Err(_) => self.pact.boxed(),
+        };
+
+        let path = self.pact_file.output_dir().join(pact.default_file_name());
+        write_pact(
+            pact,
+            &path,
+            PactSpecification::V4,
+            self.pact_file.overwrite(),
+        )?;
+        Ok(path)
+    }
+}
+
+impl ValidatingMockServer for PluginMockServer {
+    fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    fn path(&self, path: &str) -> Url {
+        self.url.join(path).expect("could not parse URL")
+    }
+
+    // Plugin failures are not match results, so verification goes through
+    // `results` instead.
+    fn status(&self) -> Vec<MatchResult> {
+        Vec::new()
+    }
+
+    fn metrics(&self) -> MockServerMetrics {
+        MockServerMetrics::default()
+    }
+}
+
+impl Drop for PluginMockServer {
+    fn drop(&mut self) {
+        if !self.running {
+            return;
+        }
+
+        match self.stop() {
+            Ok(Shutdown::PluginMismatches(mismatches)) => log::error!(
+                "Plugin mock server {}/{} failed verification for {}",
+                self.pact.consumer().name,
+                self.pact.provider().name,
+                mismatches
+                    .iter()
+                    .map(|m| m.path.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Ok(_) => {}
+            Err(e) => log::error!("{}", e),
+        }
+    }
+}
+
+/// Converts the results the plugin reports, keeping only the failures.
+/// Plugins describe mismatches as text, so they become body mismatches, or
+/// metadata mismatches where the plugin says so.
+fn plugin_mismatches(results: Vec<MockServerResults>) -> Vec<NifPluginMismatch> {
+    results
+        .into_iter()
+        .filter(|r| !(r.error.is_empty() && r.mismatches.is_empty()))
+        .map(|r| NifPluginMismatch {
+            path: r.path,
+            error: Some(r.error).filter(|error| !error.is_empty()),
+            mismatches: r
+                .mismatches
+                .into_iter()
+                .map(|m| match m.mismatch_type.as_deref() {
+                    Some("metadata") => Mismatch::MetadataMismatch {
+                        key: m.path,
+                        expected: m.expected,
+                        actual: m.actual,
+                        mismatch: m.mismatch,
+                    },
+                    _ => Mismatch::BodyMismatch {
+                        path: m.path,
+                        expected: Some(m.expected.into()),
+                        actual: Some(m.actual.into()),
+                        mismatch: m.mismatch,
+                    },
+                })
+                .map(Into::into)
+                .collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use pact_mock_server::{matching::MatchResult, mock_server::MockServer};
+use pact_models::v4::synch_http::SynchronousHttp;
+use rustler::{Encoder, LocalPid, OwnedEnv};
+use tokio::{sync::oneshot, task::JoinHandle, time};
+
+use crate::{models::match_result::NifMatchResult, runtime};
+
+rustler::atoms! {
+    pact_request,
+    pact_mismatch,
+}
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Sends `{:pact_request, method, path, matched?}` to an Elixir process for
+/// every request a mock server records, followed by `{:pact_mismatch, result}`
+/// for those that did not match. `pact_mock_server` offers no hook into its
+/// event loop, so the recorded match results are watched instead.
+pub struct RequestNotifier {
+    stop_tx: Option<oneshot::Sender<()>>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl RequestNotifier {
+    pub fn start(mock_server: MockServer, owner: LocalPid) -> Self {
+        let (stop_tx, mut stop_rx) = oneshot::channel();
+
+        let task = runtime::runtime().spawn(async move {
+            let interactions = mock_server
+                .pact
+                .interactions
+                .iter()
+                .filter_map(|i| i.as_v4_http())
+                .collect::<Vec<_>>();
+            let mut notified = 0;
+            let mut interval = time::interval(POLL_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = &mut stop_rx => break,
+                    _ = interval.tick() => {},
+                }
+                // Cloning the match results is only worth it once a request
+                // has been received that was not notified yet.
+                if received(&mock_server) > notified {
+                    notified = notify(&mock_server, &interactions, &owner, notified);
+                }
+            }
+            notify(&mock_server, &interactions, &owner, notified);
+        });
+
+        Self {
+            stop_tx: Some(stop_tx),
+            task: Some(task),
+        }
+    }
+}
+
+/// Sends the notifications still outstanding and stops watching. The notifier
+/// must be dropped after the mock server has shut down, so that no request is
+/// missed.
+impl Drop for RequestNotifier {
+    fn drop(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(task) = self.task.take() {
+            let _ = runtime::block_on(task);
+        }
+    }
+}
+
+/// The number of requests the mock server has received. Each is recorded as
+/// a match result shortly after, once it has been matched.
+fn received(mock_server: &MockServer) -> usize {
+    mock_server
+        .metrics
+        .lock()
+        .map_or(0, |metrics| metrics.requests)
+}
+
+/// Notifies the owner of the match results recorded after the first
+/// `notified` ones and returns how many have been notified in total.
+fn notify(
+    mock_server: &MockServer,
+    interactions: &[SynchronousHttp],
+    owner: &LocalPid,
+    notified: usize,
+) -> usize {
+    let matches = mock_server.matches();
+    for result in matches.iter().skip(notified) {
+        let Some((method, path)) = request_line(result) else {
+            continue;
+        };
+        let matched = result.matched() || result.cors_preflight();
+        let _ = OwnedEnv::new().send_and_clear(owner, |env| {
+            (pact_request(), method, path, matched).encode(env)
+        });
+        if !matched {
+            let result = NifMatchResult::new(result.clone(), interactions);
+            let _ =
+                OwnedEnv::new().send_and_clear(owner, |env| (pact_mismatch(), result).encode(env));
+        }
+    }
+    matches.len().max(notified)
+}
+
+fn request_line(result: &MatchResult) -> Option<(String, String)> {
+    match result {
+        MatchResult::RequestMatch(_, _, request)
+        | MatchResult::RequestMismatch(_, request, _)
+        | MatchResult::RequestNotFound(request) => {
+            Some((request.method.clone(), request.path.clone()))
+        }
+        MatchResult::MissingRequest(_) => None,
+    }
+}
@@ -1,4 +1,9 @@
+pub mod binary;
 pub mod interaction;
+pub mod match_result;
+pub mod mismatch;
+pub mod received_request;
 pub mod request;
+pub mod request_details;
 pub mod response;
 pub mod v4;
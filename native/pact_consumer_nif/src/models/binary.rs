@@ -0,0 +1,13 @@
+use bytes::Bytes;
+use rustler::{Encoder, Env, Term, types::binary::NewBinary};
+
+/// Raw bytes, encoded as an Elixir binary rather than a list of integers.
+pub struct NifBinary(pub Bytes);
+
+impl Encoder for NifBinary {
+    fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
+        let mut binary = NewBinary::new(env, self.0.len());
+        binary.as_mut_slice().copy_from_slice(&self.0);
+        Term::from(binary)
+    }
+}
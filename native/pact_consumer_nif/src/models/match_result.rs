@@ -0,0 +1,109 @@
+use pact_mock_server::matching::MatchResult;
+use pact_models::v4::{http_parts::HttpRequest, synch_http::SynchronousHttp};
+use rustler::{NifStruct, NifUntaggedEnum};
+
+use crate::models::{mismatch::NifMismatch, request_details::NifRequestDetails};
+
+#[derive(NifUntaggedEnum)]
+#[rustler(encode)]
+pub enum NifMatchResult {
+    RequestMatch(NifRequestMatch),
+    RequestMismatch(NifRequestMismatch),
+    RequestNotFound(NifRequestNotFound),
+    MissingRequest(NifMissingRequest),
+    PluginMismatch(NifPluginMismatch),
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.MatchResult.RequestMatch"]
+pub struct NifRequestMatch {
+    pub interaction: Option<String>,
+    pub expected: NifRequestDetails,
+    pub actual: NifRequestDetails,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.MatchResult.RequestMismatch"]
+pub struct NifRequestMismatch {
+    pub interaction: Option<String>,
+    pub expected: NifRequestDetails,
+    pub actual: NifRequestDetails,
+    pub mismatches: Vec<NifMismatch>,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.MatchResult.RequestNotFound"]
+pub struct NifRequestNotFound {
+    pub request: NifRequestDetails,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.MatchResult.MissingRequest"]
+pub struct NifMissingRequest {
+    pub interaction: Option<String>,
+    pub request: NifRequestDetails,
+}
+
+/// A failure reported by the plugin behind a mock server for a transport such
+/// as gRPC. Plugins only report the path, e.g. the gRPC method, along with an
+/// error or mismatches described as text, not the requests themselves.
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.MatchResult.PluginMismatch"]
+pub struct NifPluginMismatch {
+    pub path: String,
+    pub error: Option<String>,
+    pub mismatches: Vec<NifMismatch>,
+}
+
+/// The description of the interaction the expected request belongs to.
+pub fn interaction_description(
+    interactions: &[SynchronousHttp],
+    request: &HttpRequest,
+) -> Option<String> {
+    interactions
+        .iter()
+        .find(|interaction| &interaction.request == request)
+        .map(|interaction| interaction.description.clone())
+}
+
+impl NifMatchResult {
+    /// Converts a mock server match result, resolving the description of the
+    /// interaction the expected request belongs to.
+    pub fn new(result: MatchResult, interactions: &[SynchronousHttp]) -> Self {
+        let description = |request: &HttpRequest| interaction_description(interactions, request);
+
+        match result {
+            MatchResult::RequestMatch(expected, _response, actual) => {
+                NifMatchResult::RequestMatch(NifRequestMatch {
+                    interaction: description(&expected),
+                    expected: expected.into(),
+                    actual: actual.into(),
+                })
+            }
+            MatchResult::RequestMismatch(expected, actual, mismatches) => {
+                NifMatchResult::RequestMismatch(NifRequestMismatch {
+                    interaction: description(&expected),
+                    expected: expected.into(),
+                    actual: actual.into(),
+                    mismatches: mismatches.into_iter().map(Into::into).collect(),
+                })
+            }
+            MatchResult::RequestNotFound(request) => {
+                NifMatchResult::RequestNotFound(NifRequestNotFound {
+                    request: request.into(),
+                })
+            }
+            MatchResult::MissingRequest(request) => {
+                NifMatchResult::MissingRequest(NifMissingRequest {
+                    interaction: description(&request),
+                    request: request.into(),
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use bytes::Bytes;
+use pact_matching::Mismatch;
+use rustler::{NifStruct, NifUntaggedEnum};
+
+use crate::models::binary::NifBinary;
+
+#[derive(NifUntaggedEnum)]
+#[rustler(encode)]
+pub enum NifMismatch {
+    Method(NifMethodMismatch),
+    Path(NifPathMismatch),
+    Status(NifStatusMismatch),
+    Query(NifQueryMismatch),
+    Header(NifHeaderMismatch),
+    BodyType(NifBodyTypeMismatch),
+    Body(NifBodyMismatch),
+    Metadata(NifMetadataMismatch),
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.Mismatch.MethodMismatch"]
+pub struct NifMethodMismatch {
+    pub expected: String,
+    pub actual: String,
+    pub mismatch: String,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.Mismatch.PathMismatch"]
+pub struct NifPathMismatch {
+    pub expected: String,
+    pub actual: String,
+    pub mismatch: String,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.Mismatch.StatusMismatch"]
+pub struct NifStatusMismatch {
+    pub expected: u16,
+    pub actual: u16,
+    pub mismatch: String,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.Mismatch.QueryMismatch"]
+pub struct NifQueryMismatch {
+    pub parameter: String,
+    pub expected: String,
+    pub actual: String,
+    pub mismatch: String,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.Mismatch.HeaderMismatch"]
+pub struct NifHeaderMismatch {
+    pub key: String,
+    pub expected: String,
+    pub actual: String,
+    pub mismatch: String,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.Mismatch.BodyTypeMismatch"]
+pub struct NifBodyTypeMismatch {
+    pub expected: String,
+    pub actual: String,
+    pub mismatch: String,
+    pub expected_body: Option<NifBinary>,
+    pub actual_body: Option<NifBinary>,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.Mismatch.BodyMismatch"]
+pub struct NifBodyMismatch {
+    pub path: String,
+    pub expected: Option<NifBinary>,
+    pub actual: Option<NifBinary>,
+    pub mismatch: String,
+}
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.Mismatch.MetadataMismatch"]
+pub struct NifMetadataMismatch {
+    pub key: String,
+    pub expected: String,
+    pub actual: String,
+    pub mismatch: String,
+}
+
+fn binary(bytes: Option<Bytes>) -> Option<NifBinary> {
+    bytes.map(NifBinary)
+}
+
+impl From<Mismatch> for NifMismatch {
+    fn from(mismatch: Mismatch) -> Self {
+        match mismatch {
+            Mismatch::MethodMismatch {
+                expected,
+                actual,
+                mismatch,
+            } => NifMismatch::Method(NifMethodMismatch {
+                expected,
+                actual,
+                mismatch,
+            }),
+            Mismatch::PathMismatch {
+                expected,
+                actual,
+                mismatch,
+            } => NifMismatch::Path(NifPathMismatch {
+                expected,
+                actual,
+                mismatch,
+            }),
+            Mismatch::StatusMismatch {
+                expected,
+                actual,
+                mismatch,
+            } => NifMismatch::Status(NifStatusMismatch {
+                expected,
+                actual,
+                mismatch,
+            }),
+            Mismatch::QueryMismatch {
+                parameter,
+                expected,
+                actual,
+                mismatch,
+            } => NifMismatch::Query(NifQueryMismatch {
+                parameter,
+                expected,
+                actual,
+                mismatch,
+            }),
+            Mismatch::HeaderMismatch {
+                key,
+                expected,
+                actual,
+                mismatch,
+            } => NifMismatch::Header(NifHeaderMismatch {
+                key,
+                expected,
+                actual,
+                mismatch,
+            }),
+            Mismatch::BodyTypeMismatch {
+                expected,
+                actual,
+                mismatch,
+                expected_body,
+                actual_body,
+            } => NifMismatch::BodyType(NifBodyTypeMismatch {
+                expected,
+                actual,
+                mismatch,
+                expected_body: binary(expected_body),
+                actual_body: binary(actual_body),
+            }),
+            Mismatch::BodyMismatch {
+                path,
+                expected,
+                actual,
+                mismatch,
+            } => NifMismatch::Body(NifBodyMismatch {
+                path,
+                expected: binary(expected),
+                actual: binary(actual),
+                mismatch,
+            }),
+            Mismatch::MetadataMismatch {
+                key,
+                expected,
+                actual,
+                mismatch,
+            } => NifMismatch::Metadata(NifMetadataMismatch {
+                key,
+                expected,
+                actual,
+                mismatch,
+            }),
+        }
+    }
+}
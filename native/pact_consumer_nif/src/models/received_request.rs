@@ -0,0 +1,39 @@
+use pact_mock_server::matching::MatchResult;
+use pact_models::v4::synch_http::SynchronousHttp;
+use rustler::NifStruct;
+
+use crate::models::{match_result::interaction_description, request_details::NifRequestDetails};
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.ReceivedRequest"]
+pub struct NifReceivedRequest {
+    pub request: NifRequestDetails,
+    pub interaction: Option<String>,
+    pub matched: bool,
+}
+
+impl NifReceivedRequest {
+    /// Converts the match result recorded for a received request. Results
+    /// that do not stem from a received request yield `None`.
+    pub fn new(result: MatchResult, interactions: &[SynchronousHttp]) -> Option<Self> {
+        match result {
+            MatchResult::RequestMatch(expected, _response, actual) => Some(Self {
+                interaction: interaction_description(interactions, &expected),
+                request: actual.into(),
+                matched: true,
+            }),
+            MatchResult::RequestMismatch(expected, actual, _mismatches) => Some(Self {
+                interaction: interaction_description(interactions, &expected),
+                request: actual.into(),
+                matched: false,
+            }),
+            MatchResult::RequestNotFound(actual) => Some(Self {
+                interaction: None,
+                request: actual.into(),
+                matched: false,
+            }),
+            MatchResult::MissingRequest(_expected) => None,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use pact_models::v4::http_parts::HttpRequest;
+use rustler::NifStruct;
+
+use crate::models::binary::NifBinary;
+
+#[derive(NifStruct)]
+#[rustler(encode)]
+#[module = "Pact.Models.RequestDetails"]
+pub struct NifRequestDetails {
+    pub method: String,
+    pub path: String,
+    pub query: Option<HashMap<String, Vec<Option<String>>>>,
+    pub headers: Option<HashMap<String, Vec<String>>>,
+    pub body: Option<NifBinary>,
+}
+
+impl From<HttpRequest> for NifRequestDetails {
+    fn from(request: HttpRequest) -> Self {
+        Self {
+            body: request.body.value().map(NifBinary),
+            method: request.method,
+            path: request.path,
+            query: request.query,
+            headers: request.headers,
+        }
+    }
+}
@@ -1,11 +1,74 @@
-use pact_consumer::prelude::{DateTime, EachLike, JsonPattern, Like, StringPattern, Term};
+use pact_consumer::{
+    patterns::{EachKey, EachValue, ObjectMatching, Pattern},
+    prelude::{DateTime, JsonPattern, Like, StringPattern, Term},
+};
+use pact_matching::{CommonMismatch, CoreMatchingContext, DiffConfig, json::compare_json};
+use pact_models::{
+    matchingrules::{MatchingRule, MatchingRuleCategory, RuleLogic},
+    path_exp::DocPath,
+};
 use regex::Regex;
-use rustler::{Atom, Decoder, Encoder, NifTaggedEnum, NifUntaggedEnum};
+use rustler::{Atom, BigInt, Decoder, Encoder, NifResult, NifTaggedEnum, NifUntaggedEnum};
+use serde_json::Value;
 use std::collections::HashMap;
 
+mod array_contains;
+mod combined;
+mod each_like;
+mod generators;
+mod matching;
+
+use array_contains::ArrayContains;
+use combined::Combined;
+use each_like::EachLike;
+use generators::NifGenerator;
+pub use generators::{add_body_generators, add_header_generator};
+use matching::Matching;
+
+/// Examples generated by the type matchers when none is given.
+const INTEGER_EXAMPLE: i64 = 13;
+const DECIMAL_EXAMPLE: f64 = 13.01;
+
 mod atoms {
     rustler::atoms! {
-        invalid_json_object_key
+        invalid_json_object_key,
+        invalid_regex,
+        invalid_examples,
+        empty_combination,
+    }
+}
+
+/// An error in a pattern that was decoded successfully but cannot be turned
+/// into a `pact_consumer` pattern.
+#[derive(Debug)]
+pub enum PatternError {
+    /// A regex that does not compile.
+    InvalidRegex { path: DocPath, message: String },
+    /// Examples that do not satisfy the matchers they are given with.
+    InvalidExamples(Vec<CommonMismatch>),
+    /// An `all_of` or `any_of` matcher without any patterns.
+    EmptyCombination { path: DocPath },
+}
+
+impl From<PatternError> for rustler::Error {
+    fn from(error: PatternError) -> Self {
+        match error {
+            PatternError::InvalidRegex { path, message } => rustler::Error::RaiseTerm(Box::new((
+                atoms::invalid_regex(),
+                path.to_string(),
+                message,
+            ))),
+            PatternError::InvalidExamples(mismatches) => rustler::Error::RaiseTerm(Box::new((
+                atoms::invalid_examples(),
+                mismatches
+                    .iter()
+                    .map(|m| (m.path.clone(), m.to_string()))
+                    .collect::<Vec<_>>(),
+            ))),
+            PatternError::EmptyCombination { path } => {
+                rustler::Error::RaiseTerm(Box::new((atoms::empty_combination(), path.to_string())))
+            }
+        }
     }
 }
 
@@ -39,10 +102,33 @@ impl Encoder for JsonObjectKey {
     }
 }
 
+/// A JSON number, kept as an integer or a float as written. Integers beyond
+/// `u64` do not fit a JSON number without losing digits, so they are written
+/// as strings instead.
+#[derive(NifUntaggedEnum)]
+pub enum NifNumber {
+    Integer(i64),
+    UnsignedInteger(u64),
+    BigInteger(BigInt),
+    Float(f64),
+}
+
+impl From<NifNumber> for JsonPattern {
+    fn from(value: NifNumber) -> Self {
+        let number = match value {
+            NifNumber::Integer(integer) => Value::from(integer),
+            NifNumber::UnsignedInteger(integer) => Value::from(integer),
+            NifNumber::BigInteger(integer) => Value::String(integer.to_string()),
+            NifNumber::Float(float) => Value::from(float),
+        };
+        JsonPattern::Json(number)
+    }
+}
+
 #[derive(NifUntaggedEnum)]
 pub enum NifJsonPattern {
     String(String),
-    Number(f64),
+    Number(NifNumber),
     Bool(bool),
     Array(Vec<NifJsonPattern>),
     Null(Atom),
@@ -60,54 +146,205 @@ pub enum NifJsonMatcher {
     EachLike {
         json_pattern: Box<NifJsonPattern>,
         min_len: usize,
+        max_len: Option<usize>,
     },
     DateTime {
         format: String,
         example: String,
     },
+    Integer(Option<NifNumber>),
+    Decimal(Option<f64>),
+    Number(Option<NifNumber>),
+    Boolean(Option<bool>),
+    Null(Atom),
+    Include(String),
+    NotEmpty(Box<NifJsonPattern>),
+    MinType {
+        json_pattern: Box<NifJsonPattern>,
+        min: usize,
+    },
+    MaxType {
+        json_pattern: Box<NifJsonPattern>,
+        max: usize,
+    },
+    EachKey {
+        json_pattern: Box<NifJsonPattern>,
+        key_pattern: NifStringPattern,
+    },
+    EachValue {
+        json_pattern: Box<NifJsonPattern>,
+        value_pattern: Box<NifJsonPattern>,
+    },
+    ArrayContains(Vec<NifJsonPattern>),
+    Semver(String),
+    ContentType {
+        content_type: String,
+        example: String,
+    },
+    Generated {
+        pattern: Box<NifJsonPattern>,
+        generator: NifGenerator,
+    },
+    AllOf(Vec<NifJsonPattern>),
+    AnyOf(Vec<NifJsonPattern>),
+}
+
+impl TryFrom<NifJsonPattern> for JsonPattern {
+    type Error = PatternError;
+
+    fn try_from(value: NifJsonPattern) -> Result<Self, Self::Error> {
+        let pattern = value.into_pattern(DocPath::root())?;
+        validate(matching_rules(&pattern), &pattern.to_example())?;
+        Ok(pattern)
+    }
 }
 
-impl From<NifJsonPattern> for JsonPattern {
-    fn from(value: NifJsonPattern) -> Self {
-        match value {
+impl NifJsonPattern {
+    /// Converts this pattern, `path` being its location in the example, so
+    /// that errors can point at the offending matcher.
+    fn into_pattern(self, path: DocPath) -> Result<JsonPattern, PatternError> {
+        Ok(match self {
             NifJsonPattern::String(string) => string.into(),
             NifJsonPattern::Number(number) => number.into(),
             NifJsonPattern::Bool(bool) => bool.into(),
             NifJsonPattern::Array(array) => array
                 .into_iter()
-                .map(Into::into)
-                .collect::<Vec<JsonPattern>>()
+                .enumerate()
+                .map(|(index, element)| element.into_pattern(path.join_index(index)))
+                .collect::<Result<Vec<JsonPattern>, _>>()?
                 .into(),
             NifJsonPattern::Object(object) => object
                 .into_iter()
-                .map(|(k, v)| (k.0, v.into()))
-                .collect::<HashMap<String, JsonPattern>>()
+                .map(|(k, v)| {
+                    let value = v.into_pattern(path.join_field(&k.0))?;
+                    Ok((k.0, value))
+                })
+                .collect::<Result<HashMap<String, JsonPattern>, _>>()?
                 .into(),
-            NifJsonPattern::Matcher(matcher) => matcher.into(),
+            NifJsonPattern::Matcher(matcher) => matcher.into_pattern(path)?,
             NifJsonPattern::Null(_atom) => JsonPattern::null(),
-        }
+        })
     }
 }
 
-impl From<NifJsonMatcher> for JsonPattern {
-    fn from(value: NifJsonMatcher) -> Self {
-        match value {
+impl NifJsonMatcher {
+    fn into_pattern(self, path: DocPath) -> Result<JsonPattern, PatternError> {
+        Ok(match self {
             NifJsonMatcher::MatchingRegex { regex, example } => {
-                Term::<JsonPattern>::new(Regex::new(&regex).expect("valid regex"), example).into()
+                Term::<JsonPattern>::new(parse_regex(&regex, &path)?, example).into()
             }
             NifJsonMatcher::Like(json_pattern) => {
-                Like::<JsonPattern>::new::<JsonPattern>((*json_pattern).into()).into()
+                Like::<JsonPattern>::new::<JsonPattern>(json_pattern.into_pattern(path)?).into()
             }
             NifJsonMatcher::EachLike {
                 json_pattern,
                 min_len,
-            } => EachLike::new((*json_pattern).into())
-                .with_min_len(min_len)
-                .into(),
+                max_len,
+            } => {
+                let mut element_path = path;
+                element_path.push_star_index();
+                EachLike::new(json_pattern.into_pattern(element_path)?, min_len, max_len).into()
+            }
             NifJsonMatcher::DateTime { format, example } => {
                 DateTime::<JsonPattern>::new(format, example).into()
             }
+            // A float example is rejected when the examples are validated.
+            NifJsonMatcher::Integer(example) => Matching::<JsonPattern>::new(
+                example.map_or(INTEGER_EXAMPLE.into(), JsonPattern::from),
+                vec![MatchingRule::Integer],
+            )
+            .into(),
+            NifJsonMatcher::Decimal(example) => Matching::<JsonPattern>::new(
+                example.unwrap_or(DECIMAL_EXAMPLE),
+                vec![MatchingRule::Decimal],
+            )
+            .into(),
+            NifJsonMatcher::Number(example) => Matching::<JsonPattern>::new(
+                example.map_or(INTEGER_EXAMPLE.into(), JsonPattern::from),
+                vec![MatchingRule::Number],
+            )
+            .into(),
+            NifJsonMatcher::Boolean(example) => {
+                Matching::<JsonPattern>::new(example.unwrap_or(true), vec![MatchingRule::Boolean])
+                    .into()
+            }
+            NifJsonMatcher::Null(_atom) => {
+                Matching::<JsonPattern>::new(JsonPattern::null(), vec![MatchingRule::Null]).into()
+            }
+            NifJsonMatcher::Include(value) => {
+                Matching::<JsonPattern>::new(value.clone(), vec![MatchingRule::Include(value)])
+                    .into()
+            }
+            NifJsonMatcher::NotEmpty(json_pattern) => Matching::<JsonPattern>::new(
+                json_pattern.into_pattern(path)?,
+                vec![MatchingRule::NotEmpty],
+            )
+            .into(),
+            NifJsonMatcher::MinType { json_pattern, min } => Matching::<JsonPattern>::new(
+                json_pattern.into_pattern(path)?,
+                vec![MatchingRule::MinType(min)],
+            )
+            .into(),
+            NifJsonMatcher::MaxType { json_pattern, max } => Matching::<JsonPattern>::new(
+                json_pattern.into_pattern(path)?,
+                vec![MatchingRule::MaxType(max)],
+            )
+            .into(),
+            matcher @ (NifJsonMatcher::EachKey { .. } | NifJsonMatcher::EachValue { .. }) => {
+                object_matching(NifJsonPattern::Matcher(matcher), path, Vec::new())?
+            }
+            NifJsonMatcher::ArrayContains(variants) => ArrayContains::new(
+                variants
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, variant)| variant.into_pattern(path.join_index(index)))
+                    .collect::<Result<_, _>>()?,
+            )
+            .into(),
+            NifJsonMatcher::Semver(example) => {
+                Matching::<JsonPattern>::new(example, vec![MatchingRule::Semver]).into()
+            }
+            NifJsonMatcher::ContentType {
+                content_type,
+                example,
+            } => {
+                Matching::<JsonPattern>::new(example, vec![MatchingRule::ContentType(content_type)])
+                    .into()
+            }
+            // The generator is extracted separately, see `NifJsonPattern::generators`.
+            NifJsonMatcher::Generated { pattern, .. } => pattern.into_pattern(path)?,
+            NifJsonMatcher::AllOf(patterns) => combined(patterns, path, RuleLogic::And)?,
+            NifJsonMatcher::AnyOf(patterns) => combined(patterns, path, RuleLogic::Or)?,
+        })
+    }
+}
+
+/// Builds an object matched by `each_key` and `each_value` rules. Nested
+/// `each_key`/`each_value` matchers are collapsed into one, so that the keys
+/// and the values of the same object can both be matched.
+fn object_matching(
+    pattern: NifJsonPattern,
+    path: DocPath,
+    mut rules: Vec<MatchingRule>,
+) -> Result<JsonPattern, PatternError> {
+    match pattern {
+        NifJsonPattern::Matcher(NifJsonMatcher::EachKey {
+            json_pattern,
+            key_pattern,
+        }) => {
+            rules.push(EachKey::new(key_pattern.into_pattern(path.clone())?).into());
+            object_matching(*json_pattern, path, rules)
         }
+        NifJsonPattern::Matcher(NifJsonMatcher::EachValue {
+            json_pattern,
+            value_pattern,
+        }) => {
+            let mut values_path = path.clone();
+            values_path.push_star();
+            rules.push(EachValue::new(value_pattern.into_pattern(values_path)?).into());
+            object_matching(*json_pattern, path, rules)
+        }
+        example => Ok(ObjectMatching::new(example.into_pattern(path)?, rules).into()),
     }
 }
 
@@ -121,32 +358,194 @@ pub enum NifStringPattern {
 
 #[derive(NifTaggedEnum)]
 pub enum NifStringMatcher {
-    MatchingRegex { regex: String, example: String },
+    MatchingRegex {
+        regex: String,
+        example: String,
+    },
     Like(Box<NifStringPattern>),
-    DateTime { format: String, example: String },
+    DateTime {
+        format: String,
+        example: String,
+    },
+    Boolean(Option<bool>),
+    Null(Atom),
+    Include(String),
+    NotEmpty(Box<NifStringPattern>),
+    Semver(String),
+    ContentType {
+        content_type: String,
+        example: String,
+    },
+    Generated {
+        pattern: Box<NifStringPattern>,
+        generator: NifGenerator,
+    },
+    AllOf(Vec<NifStringPattern>),
+    AnyOf(Vec<NifStringPattern>),
 }
 
-impl From<NifStringPattern> for StringPattern {
-    fn from(value: NifStringPattern) -> Self {
-        match value {
-            NifStringPattern::String(string) => string.into(),
-            NifStringPattern::Matcher(matcher) => matcher.into(),
+impl TryFrom<NifStringPattern> for StringPattern {
+    type Error = PatternError;
+
+    fn try_from(value: NifStringPattern) -> Result<Self, Self::Error> {
+        let pattern = value.into_pattern(DocPath::root())?;
+        let mut rules = matching_rules(&pattern);
+        // A string is never null, e.g. in a header, so the rules that `null()`
+        // takes part in cannot be checked against the example.
+        rules
+            .rules
+            .retain(|_, list| !list.rules.contains(&MatchingRule::Null));
+        validate(rules, &Value::String(pattern.to_example()))?;
+        Ok(pattern)
+    }
+}
+
+impl NifStringPattern {
+    /// Converts this pattern, `path` being its location in the enclosing JSON
+    /// pattern, or the root for headers and bodies.
+    fn into_pattern(self, path: DocPath) -> Result<StringPattern, PatternError> {
+        match self {
+            NifStringPattern::String(string) => Ok(string.into()),
+            NifStringPattern::Matcher(matcher) => matcher.into_pattern(path),
         }
     }
 }
 
-impl From<NifStringMatcher> for StringPattern {
-    fn from(value: NifStringMatcher) -> Self {
-        match value {
+impl NifStringMatcher {
+    fn into_pattern(self, path: DocPath) -> Result<StringPattern, PatternError> {
+        Ok(match self {
             NifStringMatcher::MatchingRegex { regex, example } => {
-                Term::<StringPattern>::new(Regex::new(&regex).expect("valid regex"), example).into()
+                Term::<StringPattern>::new(parse_regex(&regex, &path)?, example).into()
             }
             NifStringMatcher::Like(pattern) => {
-                Like::<StringPattern>::new::<StringPattern>((*pattern).into()).into()
+                Like::<StringPattern>::new::<StringPattern>(pattern.into_pattern(path)?).into()
             }
             NifStringMatcher::DateTime { format, example } => {
                 DateTime::<StringPattern>::new(format, example).into()
             }
-        }
+            NifStringMatcher::Boolean(example) => Matching::<StringPattern>::new(
+                example.unwrap_or(true).to_string(),
+                vec![MatchingRule::Boolean],
+            )
+            .into(),
+            NifStringMatcher::Null(_atom) => {
+                Matching::<StringPattern>::new(String::new(), vec![MatchingRule::Null]).into()
+            }
+            NifStringMatcher::Include(value) => {
+                Matching::<StringPattern>::new(value.clone(), vec![MatchingRule::Include(value)])
+                    .into()
+            }
+            NifStringMatcher::NotEmpty(pattern) => Matching::<StringPattern>::new(
+                pattern.into_pattern(path)?,
+                vec![MatchingRule::NotEmpty],
+            )
+            .into(),
+            NifStringMatcher::Semver(example) => {
+                Matching::<StringPattern>::new(example, vec![MatchingRule::Semver]).into()
+            }
+            NifStringMatcher::ContentType {
+                content_type,
+                example,
+            } => Matching::<StringPattern>::new(
+                example,
+                vec![MatchingRule::ContentType(content_type)],
+            )
+            .into(),
+            // The generator is extracted separately, see `NifStringPattern::generator`.
+            NifStringMatcher::Generated { pattern, .. } => pattern.into_pattern(path)?,
+            NifStringMatcher::AllOf(patterns) => combined(patterns, path, RuleLogic::And)?,
+            NifStringMatcher::AnyOf(patterns) => combined(patterns, path, RuleLogic::Or)?,
+        })
     }
 }
+
+/// Patterns that can be converted at a given path, so that `all_of` and
+/// `any_of` can combine JSON and string patterns alike.
+trait IntoPattern {
+    type Pattern: Pattern;
+
+    fn into_pattern(self, path: DocPath) -> Result<Self::Pattern, PatternError>;
+}
+
+impl IntoPattern for NifJsonPattern {
+    type Pattern = JsonPattern;
+
+    fn into_pattern(self, path: DocPath) -> Result<JsonPattern, PatternError> {
+        NifJsonPattern::into_pattern(self, path)
+    }
+}
+
+impl IntoPattern for NifStringPattern {
+    type Pattern = StringPattern;
+
+    fn into_pattern(self, path: DocPath) -> Result<StringPattern, PatternError> {
+        NifStringPattern::into_pattern(self, path)
+    }
+}
+
+/// Combines the rules of `patterns` with `logic`, the first one providing the
+/// example.
+fn combined<P>(
+    patterns: Vec<P>,
+    path: DocPath,
+    logic: RuleLogic,
+) -> Result<P::Pattern, PatternError>
+where
+    P: IntoPattern,
+    Combined<P::Pattern>: Into<P::Pattern>,
+{
+    let mut patterns = patterns
+        .into_iter()
+        .map(|pattern| pattern.into_pattern(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if patterns.is_empty() {
+        return Err(PatternError::EmptyCombination { path });
+    }
+
+    let example = patterns.remove(0);
+    Ok(Combined::new(example, patterns, logic).into())
+}
+
+fn matching_rules(pattern: &impl Pattern) -> MatchingRuleCategory {
+    let mut rules = MatchingRuleCategory::empty("body");
+    pattern.extract_matching_rules(DocPath::root(), &mut rules);
+    rules
+}
+
+/// Checks that `example` satisfies the matching rules of its own pattern, by
+/// matching the example against itself, so that a contract no provider could
+/// satisfy is reported when it is written rather than when it is verified.
+fn validate(rules: MatchingRuleCategory, example: &Value) -> Result<(), PatternError> {
+    let context =
+        CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &rules, &HashMap::new());
+
+    compare_json(&DocPath::root(), example, example, &context)
+        .map_err(PatternError::InvalidExamples)
+}
+
+fn parse_regex(regex: &str, path: &DocPath) -> Result<Regex, PatternError> {
+    Regex::new(regex).map_err(|e| PatternError::InvalidRegex {
+        path: path.clone(),
+        message: e.to_string(),
+    })
+}
+
+/// Renders a JSON pattern as its example, its matching rules and its
+/// generators, each as JSON, without building a pact.
+#[rustler::nif(name = "patterns_render")]
+pub fn render(pattern: NifJsonPattern) -> NifResult<(String, String, String)> {
+    let generators = pattern.generators()?;
+    let pattern = JsonPattern::try_from(pattern)?;
+
+    let rules = matching_rules(&pattern);
+    let generators = generators
+        .into_iter()
+        .filter_map(|(path, generator)| Some((path.to_string(), generator.to_json()?)))
+        .collect::<serde_json::Map<_, _>>();
+
+    Ok((
+        pattern.to_example().to_string(),
+        rules.to_v3_json().to_string(),
+        Value::Object(generators).to_string(),
+    ))
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use pact_consumer::{patterns::Pattern, prelude::JsonPattern};
+use pact_models::{
+    matchingrules::{MatchingRule, MatchingRuleCategory, RuleLogic},
+    path_exp::DocPath,
+};
+use serde_json::Value;
+
+/// Matches arrays containing at least one element matching each variant, in
+/// any order and alongside any other elements. Generates an array with one
+/// element per variant.
+#[derive(Debug)]
+pub struct ArrayContains {
+    variants: Vec<JsonPattern>,
+}
+
+impl ArrayContains {
+    pub fn new(variants: Vec<JsonPattern>) -> Self {
+        Self { variants }
+    }
+
+    fn rule(&self) -> MatchingRule {
+        MatchingRule::ArrayContains(
+            self.variants
+                .iter()
+                .enumerate()
+                .map(|(index, variant)| {
+                    let mut rules = MatchingRuleCategory::empty("body");
+                    variant.extract_matching_rules(DocPath::root(), &mut rules);
+                    // Variants without rules of their own must be matched
+                    // exactly, as when parsed from a pact file.
+                    if rules.is_empty() {
+                        rules.add_rule(DocPath::empty(), MatchingRule::Equality, RuleLogic::And);
+                    }
+                    (index, rules, HashMap::new())
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Pattern for ArrayContains {
+    type Matches = Value;
+
+    fn to_example(&self) -> Value {
+        Value::Array(self.variants.iter().map(Pattern::to_example).collect())
+    }
+
+    fn to_example_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&self.to_example()).unwrap_or_default()
+    }
+
+    fn extract_matching_rules(&self, path: DocPath, rules_out: &mut MatchingRuleCategory) {
+        rules_out.add_rule(path, self.rule(), RuleLogic::And);
+    }
+}
+
+impl From<ArrayContains> for JsonPattern {
+    fn from(pattern: ArrayContains) -> Self {
+        JsonPattern::pattern(pattern)
+    }
+}
@@ -0,0 +1,76 @@
+use pact_consumer::{
+    patterns::Pattern,
+    prelude::{JsonPattern, StringPattern},
+};
+use pact_models::{
+    matchingrules::{MatchingRuleCategory, RuleLogic},
+    path_exp::DocPath,
+};
+
+/// Matches values against the rules of several patterns at once, combined
+/// with `logic`. The example, and the rules for any nested values, come from
+/// the first pattern.
+#[derive(Debug)]
+pub struct Combined<Nested: Pattern> {
+    example: Nested,
+    alternatives: Vec<Nested>,
+    logic: RuleLogic,
+}
+
+impl<Nested: Pattern> Combined<Nested> {
+    pub fn new(example: Nested, alternatives: Vec<Nested>, logic: RuleLogic) -> Self {
+        Self {
+            example,
+            alternatives,
+            logic,
+        }
+    }
+}
+
+impl<Nested: Pattern> Pattern for Combined<Nested> {
+    type Matches = Nested::Matches;
+
+    fn to_example(&self) -> Self::Matches {
+        self.example.to_example()
+    }
+
+    fn to_example_bytes(&self) -> Vec<u8> {
+        self.example.to_example_bytes()
+    }
+
+    fn extract_matching_rules(&self, path: DocPath, rules_out: &mut MatchingRuleCategory) {
+        let mut rules = MatchingRuleCategory::empty(rules_out.name.clone());
+        self.example
+            .extract_matching_rules(path.clone(), &mut rules);
+        let mut combined = rules
+            .rules
+            .remove(&path)
+            .map(|list| list.rules)
+            .unwrap_or_default();
+
+        for alternative in &self.alternatives {
+            let mut alternative_rules = MatchingRuleCategory::empty(rules_out.name.clone());
+            alternative.extract_matching_rules(path.clone(), &mut alternative_rules);
+            if let Some(list) = alternative_rules.rules.remove(&path) {
+                combined.extend(list.rules);
+            }
+        }
+
+        for rule in combined {
+            rules_out.add_rule(path.clone(), rule, self.logic);
+        }
+        rules_out.add_rules(rules);
+    }
+}
+
+impl From<Combined<JsonPattern>> for JsonPattern {
+    fn from(pattern: Combined<JsonPattern>) -> Self {
+        JsonPattern::pattern(pattern)
+    }
+}
+
+impl From<Combined<StringPattern>> for StringPattern {
+    fn from(pattern: Combined<StringPattern>) -> Self {
+        StringPattern::pattern(pattern)
+    }
+}
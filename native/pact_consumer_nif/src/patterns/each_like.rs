@@ -0,0 +1,67 @@
+use std::iter::repeat_n;
+
+use pact_consumer::{patterns::Pattern, prelude::JsonPattern};
+use pact_models::{
+    matchingrules::{MatchingRule, MatchingRuleCategory, RuleLogic},
+    path_exp::DocPath,
+};
+use serde_json::Value;
+
+/// Matches arrays whose elements are like `example_element`. It mirrors
+/// `pact_consumer`'s `EachLike`, which has no way to bound the length of the
+/// array from above.
+#[derive(Debug)]
+pub struct EachLike {
+    example_element: JsonPattern,
+    min_len: usize,
+    max_len: Option<usize>,
+}
+
+impl EachLike {
+    pub fn new(example_element: JsonPattern, min_len: usize, max_len: Option<usize>) -> Self {
+        Self {
+            example_element,
+            min_len,
+            max_len,
+        }
+    }
+
+    fn rule(&self) -> MatchingRule {
+        match self.max_len {
+            Some(max_len) => MatchingRule::MinMaxType(self.min_len, max_len),
+            None => MatchingRule::MinType(self.min_len),
+        }
+    }
+}
+
+impl Pattern for EachLike {
+    type Matches = Value;
+
+    fn to_example(&self) -> Value {
+        let element = self.example_element.to_example();
+        Value::Array(repeat_n(element, self.min_len).collect())
+    }
+
+    fn to_example_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&self.to_example()).unwrap_or_default()
+    }
+
+    fn extract_matching_rules(&self, path: DocPath, rules_out: &mut MatchingRuleCategory) {
+        rules_out.add_rule(path.clone(), self.rule(), RuleLogic::And);
+
+        let mut fields_path = path.clone();
+        fields_path.push_star_index().push_star();
+        rules_out.add_rule(fields_path, MatchingRule::Type, RuleLogic::And);
+
+        let mut example_path = path;
+        example_path.push_star_index();
+        self.example_element
+            .extract_matching_rules(example_path, rules_out);
+    }
+}
+
+impl From<EachLike> for JsonPattern {
+    fn from(pattern: EachLike) -> Self {
+        JsonPattern::pattern(pattern)
+    }
+}
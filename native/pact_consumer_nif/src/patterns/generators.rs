@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use pact_models::{
+    generators::{Generator, GeneratorCategory, Generators, UuidFormat},
+    path_exp::DocPath,
+};
+use rustler::{NifTaggedEnum, NifUnitEnum};
+
+use super::{
+    NifJsonMatcher, NifJsonPattern, NifStringMatcher, NifStringPattern, PatternError, parse_regex,
+};
+
+#[derive(NifTaggedEnum)]
+pub enum NifGenerator {
+    RandomInt {
+        min: i32,
+        max: i32,
+    },
+    RandomDecimal(u16),
+    RandomHexadecimal(u16),
+    RandomString(u16),
+    Uuid(Option<NifUuidFormat>),
+    Regex(String),
+    Date {
+        format: Option<String>,
+        expression: Option<String>,
+    },
+    Time {
+        format: Option<String>,
+        expression: Option<String>,
+    },
+    DateTime {
+        format: Option<String>,
+        expression: Option<String>,
+    },
+    MockServerUrl {
+        example: String,
+        regex: String,
+    },
+}
+
+#[derive(NifUnitEnum)]
+pub enum NifUuidFormat {
+    Simple,
+    LowerCaseHyphenated,
+    UpperCaseHyphenated,
+    Urn,
+}
+
+impl From<&NifUuidFormat> for UuidFormat {
+    fn from(value: &NifUuidFormat) -> Self {
+        match value {
+            NifUuidFormat::Simple => UuidFormat::Simple,
+            NifUuidFormat::LowerCaseHyphenated => UuidFormat::LowerCaseHyphenated,
+            NifUuidFormat::UpperCaseHyphenated => UuidFormat::UpperCaseHyphenated,
+            NifUuidFormat::Urn => UuidFormat::Urn,
+        }
+    }
+}
+
+impl NifGenerator {
+    /// Converts this generator, `path` being the location of the value it
+    /// generates, so that an invalid regex can be pointed at.
+    fn to_generator(&self, path: &DocPath) -> Result<Generator, PatternError> {
+        Ok(match self {
+            NifGenerator::RandomInt { min, max } => Generator::RandomInt(*min, *max),
+            NifGenerator::RandomDecimal(digits) => Generator::RandomDecimal(*digits),
+            NifGenerator::RandomHexadecimal(digits) => Generator::RandomHexadecimal(*digits),
+            NifGenerator::RandomString(size) => Generator::RandomString(*size),
+            NifGenerator::Uuid(format) => Generator::Uuid(format.as_ref().map(Into::into)),
+            NifGenerator::Regex(regex) => {
+                parse_regex(regex, path)?;
+                Generator::Regex(regex.clone())
+            }
+            NifGenerator::Date { format, expression } => {
+                Generator::Date(format.clone(), expression.clone())
+            }
+            NifGenerator::Time { format, expression } => {
+                Generator::Time(format.clone(), expression.clone())
+            }
+            NifGenerator::DateTime { format, expression } => {
+                Generator::DateTime(format.clone(), expression.clone())
+            }
+            NifGenerator::MockServerUrl { example, regex } => {
+                parse_regex(regex, path)?;
+                Generator::MockServerURL(example.clone(), regex.clone())
+            }
+        })
+    }
+}
+
+impl NifJsonPattern {
+    /// Collects the generators of this pattern and its nested patterns, keyed
+    /// by their path in the example. `pact_consumer`'s patterns only carry
+    /// matching rules, so generators are extracted before the conversion.
+    pub fn generators(&self) -> Result<HashMap<DocPath, Generator>, PatternError> {
+        let mut generators = HashMap::new();
+        self.extract_generators(DocPath::root(), &mut generators)?;
+        Ok(generators)
+    }
+
+    fn extract_generators(
+        &self,
+        path: DocPath,
+        generators: &mut HashMap<DocPath, Generator>,
+    ) -> Result<(), PatternError> {
+        match self {
+            NifJsonPattern::Array(array) => {
+                for (index, element) in array.iter().enumerate() {
+                    element.extract_generators(path.join_index(index), generators)?;
+                }
+            }
+            NifJsonPattern::Object(object) => {
+                for (key, value) in object {
+                    value.extract_generators(path.join_field(&key.0), generators)?;
+                }
+            }
+            NifJsonPattern::Matcher(matcher) => matcher.extract_generators(path, generators)?,
+            NifJsonPattern::String(_)
+            | NifJsonPattern::Number(_)
+            | NifJsonPattern::Bool(_)
+            | NifJsonPattern::Null(_) => {}
+        }
+        Ok(())
+    }
+}
+
+impl NifJsonMatcher {
+    fn extract_generators(
+        &self,
+        path: DocPath,
+        generators: &mut HashMap<DocPath, Generator>,
+    ) -> Result<(), PatternError> {
+        match self {
+            NifJsonMatcher::Generated { pattern, generator } => {
+                pattern.extract_generators(path.clone(), generators)?;
+                let generator = generator.to_generator(&path)?;
+                generators.insert(path, generator);
+            }
+            NifJsonMatcher::EachLike { json_pattern, .. } => {
+                let mut element_path = path;
+                element_path.push_star_index();
+                json_pattern.extract_generators(element_path, generators)?;
+            }
+            // Only the first pattern provides the example.
+            NifJsonMatcher::AllOf(patterns) | NifJsonMatcher::AnyOf(patterns) => {
+                if let Some(pattern) = patterns.first() {
+                    pattern.extract_generators(path, generators)?;
+                }
+            }
+            NifJsonMatcher::ArrayContains(variants) => {
+                for (index, variant) in variants.iter().enumerate() {
+                    variant.extract_generators(path.join_index(index), generators)?;
+                }
+            }
+            NifJsonMatcher::Like(json_pattern)
+            | NifJsonMatcher::NotEmpty(json_pattern)
+            | NifJsonMatcher::MinType { json_pattern, .. }
+            | NifJsonMatcher::MaxType { json_pattern, .. }
+            | NifJsonMatcher::EachKey { json_pattern, .. }
+            | NifJsonMatcher::EachValue { json_pattern, .. } => {
+                json_pattern.extract_generators(path, generators)?
+            }
+            NifJsonMatcher::MatchingRegex { .. }
+            | NifJsonMatcher::DateTime { .. }
+            | NifJsonMatcher::Integer(_)
+            | NifJsonMatcher::Decimal(_)
+            | NifJsonMatcher::Number(_)
+            | NifJsonMatcher::Boolean(_)
+            | NifJsonMatcher::Null(_)
+            | NifJsonMatcher::Include(_)
+            | NifJsonMatcher::Semver(_)
+            | NifJsonMatcher::ContentType { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+impl NifStringPattern {
+    /// The outermost generator of this pattern, if any. A string has no
+    /// nested values, so at most one generator applies to it.
+    pub fn generator(&self) -> Result<Option<Generator>, PatternError> {
+        match self {
+            NifStringPattern::String(_) => Ok(None),
+            NifStringPattern::Matcher(NifStringMatcher::Generated { generator, .. }) => {
+                generator.to_generator(&DocPath::root()).map(Some)
+            }
+            NifStringPattern::Matcher(
+                NifStringMatcher::Like(pattern) | NifStringMatcher::NotEmpty(pattern),
+            ) => pattern.generator(),
+            NifStringPattern::Matcher(
+                NifStringMatcher::AllOf(patterns) | NifStringMatcher::AnyOf(patterns),
+            ) => match patterns.first() {
+                Some(pattern) => pattern.generator(),
+                None => Ok(None),
+            },
+            NifStringPattern::Matcher(_) => Ok(None),
+        }
+    }
+}
+
+/// Adds the generators of a JSON body pattern to `generators`.
+pub fn add_body_generators(
+    generators: &mut Generators,
+    body_generators: HashMap<DocPath, Generator>,
+) {
+    for (path, generator) in body_generators {
+        generators.add_generator_with_subcategory(&GeneratorCategory::BODY, path, generator);
+    }
+}
+
+/// Adds the generator of a header pattern, if any, to `generators`.
+pub fn add_header_generator(generators: &mut Generators, name: &str, generator: Option<Generator>) {
+    if let Some(generator) = generator {
+        let mut path = DocPath::root();
+        path.push_field(name);
+        generators.add_generator_with_subcategory(&GeneratorCategory::HEADER, path, generator);
+    }
+}
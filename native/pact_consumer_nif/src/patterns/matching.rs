@@ -0,0 +1,56 @@
+use pact_consumer::{
+    patterns::Pattern,
+    prelude::{JsonPattern, StringPattern},
+};
+use pact_models::{
+    matchingrules::{MatchingRule, MatchingRuleCategory, RuleLogic},
+    path_exp::DocPath,
+};
+
+/// Generates its example and matches values against a list of matching rules.
+/// Covers the rules `pact_consumer` has no dedicated pattern for.
+#[derive(Debug)]
+pub struct Matching<Nested: Pattern> {
+    example: Nested,
+    rules: Vec<MatchingRule>,
+}
+
+impl<Nested: Pattern> Matching<Nested> {
+    pub fn new<E: Into<Nested>>(example: E, rules: Vec<MatchingRule>) -> Self {
+        Self {
+            example: example.into(),
+            rules,
+        }
+    }
+}
+
+impl<Nested: Pattern> Pattern for Matching<Nested> {
+    type Matches = Nested::Matches;
+
+    fn to_example(&self) -> Self::Matches {
+        self.example.to_example()
+    }
+
+    fn to_example_bytes(&self) -> Vec<u8> {
+        self.example.to_example_bytes()
+    }
+
+    fn extract_matching_rules(&self, path: DocPath, rules_out: &mut MatchingRuleCategory) {
+        for rule in &self.rules {
+            rules_out.add_rule(path.clone(), rule.clone(), RuleLogic::And);
+        }
+        self.example.extract_matching_rules(path, rules_out);
+    }
+}
+
+impl From<Matching<JsonPattern>> for JsonPattern {
+    fn from(pattern: Matching<JsonPattern>) -> Self {
+        JsonPattern::pattern(pattern)
+    }
+}
+
+impl From<Matching<StringPattern>> for StringPattern {
+    fn from(pattern: Matching<StringPattern>) -> Self {
+        StringPattern::pattern(pattern)
+    }
+}
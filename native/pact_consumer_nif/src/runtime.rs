@@ -0,0 +1,23 @@
+use std::{future::Future, sync::OnceLock};
+
+use tokio::runtime::{Builder, Runtime};
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// The Tokio runtime shared by every NIF. It is created when the library is
+/// loaded, so plugin clients and mock servers all live on the same runtime
+/// for as long as the library stays loaded.
+pub fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("pact-consumer-nif")
+            .build()
+            .expect("Could not start the Tokio runtime")
+    })
+}
+
+/// Runs a future to completion on the shared runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    runtime().block_on(future)
+}
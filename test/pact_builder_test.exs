@@ -5,11 +5,16 @@ defmodule Pact.Builders.PactBuilderTest do
   alias Pact.Builders.RequestBuilder
   alias Pact.Builders.ResponseBuilder
   alias Pact.MockServer
+  alias Pact.Models.MatchResult.MissingRequest
+  alias Pact.Models.MatchResult.RequestNotFound
+  alias Pact.Models.ReceivedRequest
+  alias Pact.Models.RequestDetails
   alias Pact.Models.V4.AsynchronousMessage
   import Pact.Patterns
 
   use ExUnit.Case
 
+  doctest Pact.Generators
   doctest Pact.Patterns
 
   test "a_service_consumer_side_of_a_pact_goes_a_little_something_like_this" do
@@ -32,6 +37,270 @@ defmodule Pact.Builders.PactBuilderTest do
     response = HTTPoison.get!(MockServer.path(service, "/mallory"))
 
     assert %HTTPoison.Response{status_code: 200, body: "That is some good Mallory."} = response
+    assert :ok = MockServer.verify(service)
+  end
+
+  test "verify reports requests that never occurred" do
+    {:ok, service} =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> mallory_interaction()
+      |> PactBuilder.start_mock_server()
+
+    assert {:error,
+            [
+              %MissingRequest{
+                interaction: "A retrieve Mallory request",
+                request: %RequestDetails{method: "GET", path: "/mallory"}
+              }
+            ]} = MockServer.verify(service)
+  end
+
+  test "mock server responds with an example of the matched status class" do
+    {:ok, service} =
+      PactBuilder.new_v4("Consumer", "Alice Service")
+      |> PactBuilder.interaction("A retrieve missing Mallory request", "", fn ib ->
+        ib
+        |> InteractionBuilder.request(fn rb ->
+          rb |> RequestBuilder.path("/mallory")
+        end)
+        |> InteractionBuilder.response(fn rb ->
+          rb |> ResponseBuilder.status_matching(:client_error)
+        end)
+      end)
+      |> PactBuilder.start_mock_server()
+
+    response = HTTPoison.get!(MockServer.path(service, "/mallory"))
+
+    assert %HTTPoison.Response{status_code: 400} = response
+  end
+
+  test "mock server responds with generated header values" do
+    {:ok, service} =
+      PactBuilder.new_v4("Consumer", "Alice Service")
+      |> PactBuilder.interaction("A retrieve Mallory request", "", fn ib ->
+        ib
+        |> InteractionBuilder.request(fn rb ->
+          rb |> RequestBuilder.path("/mallory")
+        end)
+        |> InteractionBuilder.response(fn rb ->
+          rb
+          |> ResponseBuilder.header(
+            "x-request-id",
+            term("^[0-9A-F]{8}$", "00000000")
+            |> generate(Pact.Generators.random_hexadecimal(8))
+          )
+        end)
+      end)
+      |> PactBuilder.start_mock_server()
+
+    %HTTPoison.Response{headers: headers} = HTTPoison.get!(MockServer.path(service, "/mallory"))
+    {_, request_id} = List.keyfind(headers, "x-request-id", 0)
+
+    assert request_id =~ ~r/^[0-9A-F]{8}$/
+    refute request_id == "00000000"
+  end
+
+  test "mock server responds with generated body values" do
+    {:ok, service} =
+      PactBuilder.new_v4("Consumer", "Alice Service")
+      |> PactBuilder.interaction("A retrieve Mallory request", "", fn ib ->
+        ib
+        |> InteractionBuilder.request(fn rb ->
+          rb |> RequestBuilder.path("/mallory")
+        end)
+        |> InteractionBuilder.response(fn rb ->
+          rb
+          |> ResponseBuilder.json_body(%{
+            "id" => integer(0) |> generate(Pact.Generators.random_int(1, 100)),
+            "name" => "Mallory"
+          })
+        end)
+      end)
+      |> PactBuilder.start_mock_server()
+
+    %HTTPoison.Response{body: body} = HTTPoison.get!(MockServer.path(service, "/mallory"))
+
+    assert %{"id" => id, "name" => "Mallory"} = Jason.decode!(body)
+    assert id in 1..100
+  end
+
+  test "mock server matches numbers equal in value however they are written" do
+    {:ok, service} =
+      PactBuilder.new_v4("Consumer", "Alice Service")
+      |> PactBuilder.interaction("A create order request", "", fn ib ->
+        ib
+        |> InteractionBuilder.request(fn rb ->
+          rb
+          |> RequestBuilder.post()
+          |> RequestBuilder.path("/orders")
+          |> RequestBuilder.json_body(%{"amount" => 1.5})
+        end)
+        |> InteractionBuilder.response(fn rb ->
+          rb |> ResponseBuilder.created()
+        end)
+      end)
+      |> PactBuilder.start_mock_server()
+
+    response =
+      HTTPoison.post!(MockServer.path(service, "/orders"), ~s({"amount": 1.50}), [
+        {"content-type", "application/json"}
+      ])
+
+    assert %HTTPoison.Response{status_code: 201} = response
+    assert :ok = MockServer.verify(service)
+  end
+
+  test "mock server counts the requests it receives" do
+    {:ok, service} =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> mallory_interaction()
+      |> PactBuilder.start_mock_server()
+
+    for _ <- 1..3, do: HTTPoison.get!(MockServer.path(service, "/mallory"))
+
+    assert %{requests: 3, requests_by_path: %{"/mallory" => 3}} = MockServer.metrics(service)
+  end
+
+  test "mock server notifies its owner of the requests it receives" do
+    {:ok, service} =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> mallory_interaction()
+      |> PactBuilder.start_mock_server(owner: self())
+
+    HTTPoison.get!(MockServer.path(service, "/mallory"))
+    HTTPoison.get!(MockServer.path(service, "/eve"))
+
+    assert_receive {:pact_request, "GET", "/mallory", true}
+    assert_receive {:pact_request, "GET", "/eve", false}
+
+    assert_receive {:pact_mismatch,
+                    %RequestNotFound{request: %RequestDetails{method: "GET", path: "/eve"}}}
+
+    refute_received {:pact_mismatch, _}
+  end
+
+  test "mock server answers concurrent callers" do
+    {:ok, service} =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> PactBuilder.start_mock_server()
+
+    # Call the NIF from each task rather than through the GenServer, which
+    # would serialise the calls.
+    %MockServer{mock_server: mock_server} = :sys.get_state(service)
+    url = MockServer.url(service)
+
+    1..50
+    |> Task.async_stream(
+      fn i -> {i, Pact.Native.PactConsumer.mock_server_path(mock_server, "/mallory/#{i}")} end,
+      max_concurrency: 10
+    )
+    |> Enum.each(fn {:ok, {i, path}} -> assert path == "#{url}/mallory/#{i}" end)
+  end
+
+  test "mock server records the requests it receives" do
+    {:ok, service} =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> mallory_interaction()
+      |> PactBuilder.start_mock_server()
+
+    HTTPoison.get!(MockServer.path(service, "/mallory"))
+    HTTPoison.post!(MockServer.path(service, "/eve"), "hello")
+
+    assert [
+             %ReceivedRequest{
+               request: %RequestDetails{method: "GET", path: "/mallory"},
+               interaction: "A retrieve Mallory request",
+               matched: true
+             },
+             %ReceivedRequest{
+               request: %RequestDetails{method: "POST", path: "/eve", body: "hello"},
+               interaction: nil,
+               matched: false
+             }
+           ] = MockServer.received_requests(service)
+  end
+
+  test "mock server binds to the requested port" do
+    builder =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> mallory_interaction()
+
+    {:ok, service} = PactBuilder.start_mock_server(builder)
+    port = MockServer.port(service)
+
+    assert {:error, _reason} = PactBuilder.start_mock_server(builder, port: port)
+  end
+
+  test "mock server for an unknown transport is not started" do
+    builder =
+      PactBuilder.new_v4("Consumer", "Alice Service")
+      |> PactBuilder.interaction("A retrieve Mallory request", "", fn ib ->
+        ib |> InteractionBuilder.transport("carrier-pigeon")
+      end)
+
+    assert {:error, "Did not find a catalogue entry for key 'carrier-pigeon'"} =
+             PactBuilder.start_mock_server(builder)
+  end
+
+  test "tls mock server" do
+    {:ok, service} =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> mallory_interaction()
+      |> PactBuilder.start_mock_server(tls: true)
+
+    [{:Certificate, der, _}] = :public_key.pem_decode(MockServer.certificate(service))
+
+    response =
+      HTTPoison.get!(MockServer.path(service, "/mallory"), [], ssl: [cacerts: [der]])
+
+    assert "https://" <> _ = MockServer.url(service)
+    assert %HTTPoison.Response{status_code: 200, body: "That is some good Mallory."} = response
+    assert :ok = MockServer.verify(service)
+  end
+
+  @tag :tmp_dir
+  test "write pact to the builder's output directory", %{tmp_dir: tmp_dir} do
+    builder =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> PactBuilder.output_dir(tmp_dir)
+      |> PactBuilder.overwrite(true)
+      |> mallory_interaction()
+
+    path = Path.join(tmp_dir, "Consumer-Alice Service.json")
+
+    assert {:ok, ^path} = PactBuilder.write_pact(builder)
+    assert %{"interactions" => [%{"description" => "A retrieve Mallory request"}]} =
+             path |> File.read!() |> Jason.decode!()
+  end
+
+  @tag :tmp_dir
+  test "stopping the mock server writes the pact", %{tmp_dir: tmp_dir} do
+    {:ok, service} =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> PactBuilder.output_dir(tmp_dir)
+      |> mallory_interaction()
+      |> PactBuilder.start_mock_server()
+
+    HTTPoison.get!(MockServer.path(service, "/mallory"))
+
+    path = Path.join(tmp_dir, "Consumer-Alice Service.json")
+    assert {:ok, ^path} = MockServer.stop(service)
+    assert File.exists?(path)
+    refute Process.alive?(service)
+  end
+
+  @tag :tmp_dir
+  test "stopping the mock server reports requests that never occurred", %{tmp_dir: tmp_dir} do
+    {:ok, service} =
+      PactBuilder.new("Consumer", "Alice Service")
+      |> PactBuilder.output_dir(tmp_dir)
+      |> mallory_interaction()
+      |> PactBuilder.start_mock_server()
+
+    assert {:error, [%MissingRequest{interaction: "A retrieve Mallory request"}]} =
+             MockServer.stop(service)
+
+    assert File.ls!(tmp_dir) == []
   end
 
   test "message_client" do
@@ -59,7 +328,7 @@ defmodule Pact.Builders.PactBuilderTest do
               datetime("yyyy-MM-dd'T'HH:mm:ss.nXXX", "2022-11-17T10:29:45.507366921Z"),
             "string" => like("Bob"),
             "list" => each_like(%{"name" => "Foo"}, 2),
-            "regex_1" => term("^\d+$", "123"),
+            "regex_1" => term("^\\d+$", "123"),
             "regex_2" => matching_regex("^\\d+$", "123")
           })
         )
@@ -70,6 +339,283 @@ defmodule Pact.Builders.PactBuilderTest do
              ~c"{\"list\":[{\"name\":\"Foo\"},{\"name\":\"Foo\"}],\"regex_1\":\"123\",\"regex_2\":\"123\",\"string\":\"Bob\",\"timestamp\":\"2022-11-17T10:29:45.507366921Z\"}"
   end
 
+  test "number matchers" do
+    [message] =
+      PactBuilder.new_v4("message-provider", "message-consumer")
+      |> PactBuilder.message_interaction("order message", fn mb ->
+        mb
+        |> MessageBuilder.test_name("number matchers")
+        |> MessageBuilder.json_body(
+          json_pattern(%{
+            "id" => integer(42),
+            "amount" => decimal(9.99),
+            "quantity" => number(),
+            "count" => integer()
+          })
+        )
+      end)
+      |> PactBuilder.messages()
+
+    assert AsynchronousMessage.bytes(message) ==
+             ~c"{\"amount\":9.99,\"count\":13,\"id\":42,\"quantity\":13}"
+  end
+
+  test "numbers keep their integer or float type and all their digits" do
+    [message] =
+      PactBuilder.new_v4("message-provider", "message-consumer")
+      |> PactBuilder.message_interaction("order message", fn mb ->
+        mb
+        |> MessageBuilder.test_name("numbers")
+        |> MessageBuilder.json_body(
+          json_pattern(%{
+            "id" => 18_446_744_073_709_551_615,
+            "serial" => -98_765_432_109_876_543_210_123,
+            "quantity" => 42,
+            "price" => 1.0,
+            "total" => number(7),
+            "max" => integer(18_446_744_073_709_551_615)
+          })
+        )
+      end)
+      |> PactBuilder.messages()
+
+    assert AsynchronousMessage.bytes(message) ==
+             ~c"{\"id\":18446744073709551615,\"max\":18446744073709551615,\"price\":1.0,\"quantity\":42,\"serial\":\"-98765432109876543210123\",\"total\":7}"
+  end
+
+  test "boolean, null, include and not empty matchers" do
+    [message] =
+      PactBuilder.new_v4("message-provider", "message-consumer")
+      |> PactBuilder.message_interaction("profile message", fn mb ->
+        mb
+        |> MessageBuilder.test_name("boolean, null, include and not empty matchers")
+        |> MessageBuilder.json_body(
+          json_pattern(%{
+            "active" => boolean(),
+            "deleted_at" => null(),
+            "name" => include("Mallory"),
+            "tags" => not_empty(["admin"])
+          })
+        )
+      end)
+      |> PactBuilder.messages()
+
+    assert AsynchronousMessage.bytes(message) ==
+             ~c"{\"active\":true,\"deleted_at\":null,\"name\":\"Mallory\",\"tags\":[\"admin\"]}"
+  end
+
+  test "bounded array matchers" do
+    [message] =
+      PactBuilder.new_v4("message-provider", "message-consumer")
+      |> PactBuilder.message_interaction("page message", fn mb ->
+        mb
+        |> MessageBuilder.test_name("bounded array matchers")
+        |> MessageBuilder.json_body(
+          json_pattern(%{
+            "items" => min_max_like(%{"name" => "Foo"}, 2, 10),
+            "tags" => min_type(["a", "b", "c"], 1),
+            "links" => max_type(["self"], 5)
+          })
+        )
+      end)
+      |> PactBuilder.messages()
+
+    assert AsynchronousMessage.bytes(message) ==
+             ~c"{\"items\":[{\"name\":\"Foo\"},{\"name\":\"Foo\"}],\"links\":[\"self\"],\"tags\":[\"a\",\"b\",\"c\"]}"
+  end
+
+  test "each key and each value matchers" do
+    [message] =
+      PactBuilder.new_v4("message-provider", "message-consumer")
+      |> PactBuilder.message_interaction("users message", fn mb ->
+        mb
+        |> MessageBuilder.test_name("each key and each value matchers")
+        |> MessageBuilder.json_body(
+          %{"42" => %{"name" => "Mallory"}}
+          |> each_key(matching_regex("^\\d+$", "42"))
+          |> each_value(%{"name" => like("Mallory")})
+          |> json_pattern()
+        )
+      end)
+      |> PactBuilder.messages()
+
+    assert AsynchronousMessage.bytes(message) == ~c"{\"42\":{\"name\":\"Mallory\"}}"
+  end
+
+  test "array contains matcher" do
+    [message] =
+      PactBuilder.new_v4("message-provider", "message-consumer")
+      |> PactBuilder.message_interaction("audit message", fn mb ->
+        mb
+        |> MessageBuilder.test_name("array contains matcher")
+        |> MessageBuilder.json_body(
+          json_pattern(%{
+            "events" => array_contains([%{"type" => like("login")}, %{"type" => "logout"}])
+          })
+        )
+      end)
+      |> PactBuilder.messages()
+
+    assert AsynchronousMessage.bytes(message) ==
+             ~c"{\"events\":[{\"type\":\"login\"},{\"type\":\"logout\"}]}"
+  end
+
+  test "semver and content type matchers" do
+    [message] =
+      PactBuilder.new_v4("message-provider", "message-consumer")
+      |> PactBuilder.message_interaction("health message", fn mb ->
+        mb
+        |> MessageBuilder.test_name("semver and content type matchers")
+        |> MessageBuilder.json_body(
+          json_pattern(%{
+            "version" => semver("1.2.3"),
+            "page" => content_type("text/html", "<!DOCTYPE html><html></html>")
+          })
+        )
+      end)
+      |> PactBuilder.messages()
+
+    assert AsynchronousMessage.bytes(message) ==
+             ~c"{\"page\":\"<!DOCTYPE html><html></html>\",\"version\":\"1.2.3\"}"
+  end
+
+  @tag :tmp_dir
+  test "content type matcher in a text body", %{tmp_dir: tmp_dir} do
+    {:ok, path} =
+      PactBuilder.new_v4("Consumer", "Alice Service")
+      |> PactBuilder.output_dir(tmp_dir)
+      |> PactBuilder.interaction("A create Mallory request", "", fn ib ->
+        ib
+        |> InteractionBuilder.request(fn rb ->
+          rb
+          |> RequestBuilder.body_matching(content_type("text/xml", "<?xml version=\"1.0\"?><a/>"))
+        end)
+      end)
+      |> PactBuilder.write_pact()
+
+    assert %{"interactions" => [%{"request" => request}]} =
+             path |> File.read!() |> Jason.decode!()
+
+    assert %{"content" => "<?xml version=\"1.0\"?><a/>"} = request["body"]
+
+    assert %{"matchers" => [%{"match" => "contentType", "value" => "text/xml"}]} =
+             request["matchingRules"]["body"]["$"]
+  end
+
+  test "combined matchers" do
+    [message] =
+      PactBuilder.new_v4("message-provider", "message-consumer")
+      |> PactBuilder.message_interaction("price message", fn mb ->
+        mb
+        |> MessageBuilder.test_name("combined matchers")
+        |> MessageBuilder.json_body(
+          json_pattern(%{
+            "price" => all_of([like("100 EUR"), include("EUR")]),
+            "currency" => any_of([include("EUR"), include("USD")])
+          })
+        )
+      end)
+      |> PactBuilder.messages()
+
+    assert AsynchronousMessage.bytes(message) ==
+             ~c"{\"currency\":\"EUR\",\"price\":\"100 EUR\"}"
+  end
+
+  test "render shows the example, matching rules and generators of a pattern" do
+    assert {example, matching_rules, generators} =
+             Pact.Patterns.render(%{
+               "id" => integer(42) |> generate(Pact.Generators.random_int(1, 100)),
+               "tags" => each_like("a")
+             })
+
+    assert example == %{"id" => 42, "tags" => ["a"]}
+
+    assert matching_rules == %{
+             "$.id" => %{"combine" => "AND", "matchers" => [%{"match" => "integer"}]},
+             "$.tags" => %{"combine" => "AND", "matchers" => [%{"match" => "type", "min" => 1}]},
+             "$.tags[*].*" => %{"combine" => "AND", "matchers" => [%{"match" => "type"}]}
+           }
+
+    assert generators == %{"$.id" => %{"type" => "RandomInt", "min" => 1, "max" => 100}}
+  end
+
+  test "an invalid regex raises an error pointing at its path" do
+    error =
+      assert_raise ErlangError, fn ->
+        PactBuilder.new_v4("message-provider", "message-consumer")
+        |> PactBuilder.message_interaction("invalid regex", fn mb ->
+          mb
+          |> MessageBuilder.json_body(
+            json_pattern(%{"users" => [%{"id" => term("(\\d+", "42")}]})
+          )
+        end)
+      end
+
+    assert {:invalid_regex, "$.users[0].id", "regex parse error" <> _} = error.original
+  end
+
+  test "a generator with an invalid regex raises an error pointing at its path" do
+    error =
+      assert_raise ErlangError, fn ->
+        PactBuilder.new_v4("message-provider", "message-consumer")
+        |> PactBuilder.message_interaction("invalid generator regex", fn mb ->
+          mb
+          |> MessageBuilder.json_body(
+            json_pattern(%{"code" => like("ab") |> generate(Pact.Generators.regex("[a-z"))})
+          )
+        end)
+      end
+
+    assert {:invalid_regex, "$.code", "regex parse error" <> _} = error.original
+  end
+
+  @tag :tmp_dir
+  test "null matcher in headers and text bodies", %{tmp_dir: tmp_dir} do
+    {:ok, path} =
+      PactBuilder.new_v4("Consumer", "Alice Service")
+      |> PactBuilder.output_dir(tmp_dir)
+      |> PactBuilder.interaction("A delete Mallory request", "", fn ib ->
+        ib
+        |> InteractionBuilder.request(fn rb ->
+          rb
+          |> RequestBuilder.header("x-deleted-at", null())
+          |> RequestBuilder.header("x-request-id", term("^\\d+$", "42"))
+          |> RequestBuilder.body_matching(null())
+        end)
+      end)
+      |> PactBuilder.write_pact()
+
+    assert %{"interactions" => [%{"request" => %{"matchingRules" => rules}}]} =
+             path |> File.read!() |> Jason.decode!()
+
+    assert %{"matchers" => [%{"match" => "null"}]} = rules["header"]["x-deleted-at"]
+    assert %{"matchers" => [%{"match" => "regex"}]} = rules["header"]["x-request-id"]
+    assert %{"matchers" => [%{"match" => "null"}]} = rules["body"]["$"]
+  end
+
+  test "examples that do not satisfy their matchers raise every violation" do
+    error =
+      assert_raise ErlangError, fn ->
+        PactBuilder.new_v4("message-provider", "message-consumer")
+        |> PactBuilder.message_interaction("invalid examples", fn mb ->
+          mb
+          |> MessageBuilder.json_body(
+            json_pattern(%{
+              "id" => term("^\\d+$", "abc"),
+              "dates" => [datetime("yyyy-MM-dd", "17/11/2022")]
+            })
+          )
+        end)
+      end
+
+    assert {:invalid_examples, violations} = error.original
+
+    assert [
+             {"$.dates[0]", "Expected '17/11/2022' to match a timestamp pattern" <> _},
+             {"$.id", "Expected 'abc' to match '^\\d+$'"}
+           ] = Enum.sort(violations)
+  end
+
   test "string matchers" do
     [string_message, date_message, like_message, regex_message] =
       PactBuilder.new_v4("message-provider", "message-consumer")
@@ -139,4 +685,17 @@ defmodule Pact.Builders.PactBuilderTest do
 
     assert %HTTPoison.Response{status_code: 200, body: "Name,100,2000-01-01\n"} = response
   end
+
+  defp mallory_interaction(builder) do
+    builder
+    |> PactBuilder.interaction("A retrieve Mallory request", "", fn ib ->
+      ib
+      |> InteractionBuilder.request(fn rb ->
+        rb |> RequestBuilder.path("/mallory")
+      end)
+      |> InteractionBuilder.response(fn rb ->
+        rb |> ResponseBuilder.body("That is some good Mallory.")
+      end)
+    end)
+  end
 end